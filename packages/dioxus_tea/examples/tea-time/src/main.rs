@@ -2,9 +2,11 @@
 
 #![warn(clippy::pedantic)]
 
-use crate::model::{AppState, AppStatusUpdate, MakeTeaError, Status};
+use crate::model::{AppState, AppStatusUpdate, KettleAction, KettleState, MakeTeaError, Status};
 use dioxus::prelude::*;
-use dioxus_tea::TeaModel;
+use dioxus_tea::{tea_transition, Cmd, PureTeaModel, TeaModel, TeaNotifier, TeaStateMachine, TransitionTable};
+use gloo_timers::future::sleep;
+use std::{cell::Cell, sync::LazyLock, time::Duration};
 
 fn main() {
     launch(rsx_components::App);
@@ -15,6 +17,7 @@ mod model {
     use std::fmt::Display;
 
     #[derive(Default, Clone, PartialEq, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum Status {
         #[default]
         FetchingCup,
@@ -40,6 +43,7 @@ mod model {
     }
 
     #[derive(Clone, PartialEq, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum TeaType {
         Black,
         Green,
@@ -71,6 +75,7 @@ mod model {
     }
 
     #[derive(Clone, PartialEq, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum MakeTeaError {
         MissingTeaBag,
         WaterTooHot,
@@ -94,16 +99,117 @@ mod model {
         }
     }
 
-    #[derive(Default, Clone, PartialEq)]
+    /// The kettle is a child model of its own, embedded in the `AppState`.
+    #[derive(Default, Clone, PartialEq, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct KettleState {
+        pub temperature: u8,
+        pub heating: bool,
+    }
+
+    impl Display for KettleState {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            if self.heating {
+                write!(f, "Kettle: heating up to {}°C...", self.temperature)
+            } else if self.temperature > 0 {
+                write!(f, "Kettle: {}°C", self.temperature)
+            } else {
+                write!(f, "Kettle: cold")
+            }
+        }
+    }
+
+    #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum KettleAction {
+        Heat(u8),
+        Heated,
+    }
+
+    /// Published on the bus when the user changes the settings, which puts a fresh cup on the table.
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub struct SettingsChanged;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "derive", derive(dioxus_tea::TeaLenses))]
     pub struct AppState {
         pub status: Status,
+        pub kettle: KettleState,
     }
 
+    #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "derive", derive(dioxus_tea::TeaActions))]
     pub enum AppStatusUpdate {
+        FetchCup,
         CupFetched,
         AddWater(u8),
         AddTeaBag(TeaType),
         Done,
+        Kettle(KettleAction),
+    }
+
+    /// Without the `derive` feature, the methods that `TeaActions` would generate for the buttons are written out.
+    #[cfg(not(feature = "derive"))]
+    pub trait AppStatusUpdateActions {
+        fn fetch_cup(&self);
+        fn add_water(&self, temperature: u8);
+        fn add_tea_bag(&self, tea_type: TeaType);
+        fn kettle(&self, action: KettleAction);
+    }
+
+    #[cfg(not(feature = "derive"))]
+    impl<T: dioxus_tea::TeaModel<Action = AppStatusUpdate>> AppStatusUpdateActions for dioxus_tea::TeaModelSignal<T> {
+        #[inline]
+        fn fetch_cup(&self) {
+            self.send(AppStatusUpdate::FetchCup);
+        }
+
+        #[inline]
+        fn add_water(&self, temperature: u8) {
+            self.send(AppStatusUpdate::AddWater(temperature));
+        }
+
+        #[inline]
+        fn add_tea_bag(&self, tea_type: TeaType) {
+            self.send(AppStatusUpdate::AddTeaBag(tea_type));
+        }
+
+        #[inline]
+        fn kettle(&self, action: KettleAction) {
+            self.send(AppStatusUpdate::Kettle(action));
+        }
+    }
+
+    /// Without the `derive` feature, the lens that `TeaLenses` would generate for the options is written out.
+    #[cfg(not(feature = "derive"))]
+    pub trait AppStateLenses {
+        fn status_signal(&self) -> dioxus_tea::TeaLens<Status>;
+    }
+
+    #[cfg(not(feature = "derive"))]
+    impl AppStateLenses for dioxus_tea::TeaModelSignal<AppState> {
+        #[inline]
+        fn status_signal(&self) -> dioxus_tea::TeaLens<Status> {
+            self.map(|state| state.status.clone())
+        }
+    }
+}
+
+// The kettle is a model of its own, so it could also be used with `use_tea_model` directly. Its reducer is a pure
+// function of the previous kettle, and the `AppState` that embeds it runs the timer while the water heats up.
+impl PureTeaModel for KettleState {
+    type Action = KettleAction;
+
+    fn reduce(self, action: Self::Action) -> Self {
+        match action {
+            KettleAction::Heat(temperature) => Self {
+                temperature,
+                heating: true,
+            },
+            KettleAction::Heated => Self { heating: false, ..self },
+        }
     }
 }
 
@@ -111,8 +217,13 @@ mod model {
 impl TeaModel for AppState {
     type Action = AppStatusUpdate;
 
-    fn update(&mut self, action: Self::Action) {
+    fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
         match action {
+            AppStatusUpdate::FetchCup => {
+                // simulate fetching a cup, which takes a second
+                self.status = Status::FetchingCup;
+                return Cmd::perform(wait(Duration::from_secs(1)), |()| AppStatusUpdate::CupFetched);
+            }
             AppStatusUpdate::CupFetched => {
                 // when the cup is fetched, we start with an empty cup
                 self.status = Status::EmptyCup;
@@ -121,7 +232,7 @@ impl TeaModel for AppState {
                 let Status::TeaBag(tea_type) = &self.status else {
                     // if there is no tea bag, we can't make tea
                     self.status = Status::Error(MakeTeaError::MissingTeaBag);
-                    return;
+                    return Cmd::none();
                 };
 
                 // check that the water temperature is within a valid range for a good cup of tea
@@ -132,21 +243,25 @@ impl TeaModel for AppState {
                     self.status = Status::Error(MakeTeaError::WaterTooHot);
                 } else {
                     self.status = Status::Water(temperature);
+                    // wait for 2 seconds to simulate making tea
+                    return Cmd::perform(wait(Duration::from_secs(2)), |()| AppStatusUpdate::Done);
                 }
             }
-            AppStatusUpdate::AddTeaBag(tea_type) => {
-                match self.status {
+            AppStatusUpdate::AddTeaBag(ref tea_type) => {
+                let added = tea_transition!(self.status, {
                     Status::EmptyCup => {
-                        self.status = Status::TeaBag(tea_type);
-                    }
-                    Status::FetchingCup => {
-                        self.status = Status::Error(MakeTeaError::NoCup);
-                    }
-                    _ => {
-                        // if we are not in a state to add a tea bag, we can't add it
-                        self.status = Status::Error(MakeTeaError::CupNotEmpty);
-                    }
-                }
+                        // a toast is feedback for the user, not part of the state
+                        TeaNotifier::notify(format!("{tea_type} tea bag added!"));
+                        Status::TeaBag(tea_type.clone())
+                    },
+                    Status::FetchingCup => Err(MakeTeaError::NoCup),
+                    // if we are not in a state to add a tea bag, we can't add it
+                    _ => Err(MakeTeaError::CupNotEmpty),
+                });
+                self.status = added.unwrap_or_else(|error| {
+                    self.on_rejected(&action);
+                    Status::Error(error)
+                });
             }
             AppStatusUpdate::Done => {
                 if let Status::Water(_) = &self.status {
@@ -157,15 +272,82 @@ impl TeaModel for AppState {
                     self.status = Status::Error(MakeTeaError::MissingWater);
                 }
             }
+            AppStatusUpdate::Kettle(action) => {
+                // delegate to the child model; heating the water takes a second
+                let heat = matches!(action, KettleAction::Heat(_));
+                self.kettle = self.kettle.clone().reduce(action);
+                if heat {
+                    return Cmd::perform(wait(Duration::from_secs(1)), |()| {
+                        AppStatusUpdate::Kettle(KettleAction::Heated)
+                    });
+                }
+            }
+        }
+
+        Cmd::none()
+    }
+
+    fn cancels_effects(action: &Self::Action) -> bool {
+        // starting over with a new cup cancels the tea that is still brewing
+        matches!(action, AppStatusUpdate::FetchCup)
+    }
+
+    fn on_rejected(&self, action: &Self::Action) {
+        // count the tea bags that could not be added, e.g. to find out whether the buttons are confusing
+        if let AppStatusUpdate::AddTeaBag(_) = action {
+            REJECTED_TEA_BAGS.set(REJECTED_TEA_BAGS.get() + 1);
         }
     }
 }
 
+/// The rules of making tea, which the reducer checks by hand; `use_tea_state_machine` enforces them before `update`.
+static TRANSITIONS: LazyLock<TransitionTable<AppState, AppStatusUpdate>> = LazyLock::new(|| {
+    TransitionTable::<AppState, _>::new()
+        .allow(
+            |action| matches!(action, AppStatusUpdate::CupFetched),
+            |from| from.status == Status::FetchingCup,
+        )
+        .allow(
+            |action| matches!(action, AppStatusUpdate::AddTeaBag(_)),
+            |from| from.status == Status::EmptyCup,
+        )
+        .allow(
+            |action| matches!(action, AppStatusUpdate::AddWater(_)),
+            |from| matches!(from.status, Status::TeaBag(_)),
+        )
+        .allow(
+            |action| matches!(action, AppStatusUpdate::Done),
+            |from| matches!(from.status, Status::Water(_)),
+        )
+});
+
+impl TeaStateMachine for AppState {
+    fn allowed(from: &Self, action: &Self::Action) -> bool {
+        TRANSITIONS.allows(from, action)
+    }
+}
+
+thread_local! {
+    /// Number of `AddTeaBag` actions rejected by the reducer, as a simple metric.
+    static REJECTED_TEA_BAGS: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Sleeps for the given duration. The timer is only started once the command runs, which keeps the reducer usable
+/// in unit tests outside the browser.
+async fn wait(duration: Duration) {
+    sleep(duration).await;
+}
+
 mod rsx_components {
-    use super::model::{AppState, AppStatusUpdate, Status, TeaType};
+    use super::model::{
+        AppState, AppStateLenses, AppStatusUpdate, AppStatusUpdateActions, KettleAction, SettingsChanged, Status,
+        TeaType,
+    };
     use dioxus::prelude::*;
-    use dioxus_tea::{use_tea_model, TeaModelSignal};
-    use gloo_timers::future::sleep;
+    use dioxus_tea::{
+        use_tea_bus, use_tea_consumer, use_tea_effect, use_tea_notifications, use_tea_props, TeaModelSignal,
+        TeaProvider, TeaReadOnlySignal,
+    };
     use std::time::Duration;
 
     const MAIN_CSS: Asset = asset!("/examples/tea-time/assets/main.css");
@@ -173,14 +355,14 @@ mod rsx_components {
 
     #[component]
     pub(super) fn App() -> Element {
-        // Initialize the tea model and provide it to the context
-        let app_state = use_tea_model::<AppState>();
-        use_context_provider(|| app_state);
-
         rsx! {
             document::Link { rel: "icon", href: FAVICON }
             document::Link { rel: "stylesheet", href: MAIN_CSS }
-            DemoApp {}
+            // Initialize the tea model, start fetching a cup and provide the model to the context
+            TeaProvider::<AppState> {
+                on_init: |app_state: TeaModelSignal<AppState>| app_state.send(AppStatusUpdate::FetchCup),
+                DemoApp {}
+            }
         }
     }
 
@@ -188,95 +370,159 @@ mod rsx_components {
     fn DemoApp() -> Element {
         // Demonstrates how to use the tea model from the context. Alternatively, you could pass the
         // `TeaModelSignal<AppState>` directly to this component.
-        let app_state = use_context::<TeaModelSignal<AppState>>();
-
-        use_hook(|| {
-            // run this code once when the component mounts
-            spawn(async move {
-                // Simulate fetching a cup
-                sleep(Duration::from_secs(1)).await;
-                app_state.send(AppStatusUpdate::CupFetched);
-            });
+        let app_state = use_tea_consumer::<AppState>();
+
+        // Side effects that follow the state, like updating the window title, run whenever the state changes
+        use_tea_effect(app_state, |state| {
+            let title = if state.status == Status::TeaReady {
+                "Tea is ready! 🍵"
+            } else {
+                "Tea Time"
+            };
+            document::eval(&format!("document.title = {title:?};"));
         });
 
-        use_effect(move || {
-            let current_status = &app_state.read().status;
-            if matches!(current_status, Status::Water(_)) {
-                spawn(async move {
-                    // wait for 2 seconds to simulate making tea
-                    sleep(Duration::from_secs(2)).await;
-                    app_state.send(AppStatusUpdate::Done);
-                });
-            }
-        });
+        // The options only depend on the kind of status, so the buttons do not re-render while the kettle heats up
+        let shown = use_tea_props(app_state, ShownOptions::for_state);
 
-        let app_state_r = app_state.read();
-        let message = app_state_r.status.to_string();
+        // The settings do not know about the tea model; the model reacts to their events on the bus instead
+        let settings = use_tea_bus::<SettingsChanged>();
+        use_hook(|| app_state.connect_bus(settings, |_: &SettingsChanged| Some(AppStatusUpdate::CupFetched)));
 
         rsx! {
             div {
                 id: "title",
                 div { class: "heading", "Tea Time 🫖" }
-                div { "{message}" }
+                StatusMessage { app_state: app_state.read_only() }
             }
 
             TeaOptions {
-                app_state
+                app_state,
+                shown
+            }
+
+            Settings {}
+
+            Toasts { app_state }
+        }
+    }
+
+    #[component]
+    fn Settings() -> Element {
+        let settings = use_tea_bus::<SettingsChanged>();
+
+        rsx! {
+            button {
+                onclick: move |_| settings.publish(SettingsChanged),
+                "Reset settings"
+            }
+        }
+    }
+
+    #[component]
+    fn Toasts(app_state: TeaModelSignal<AppState>) -> Element {
+        // Notifications emitted by the reducer disappear by themselves after a few seconds
+        let notifications = use_tea_notifications(app_state, Duration::from_secs(3));
+
+        rsx! {
+            for notification in notifications.read().iter() {
+                div { key: "{notification.id}", class: "notification", "{notification.message}" }
             }
         }
     }
 
     #[component]
-    fn TeaOptions(app_state: TeaModelSignal<AppState>) -> Element {
+    fn StatusMessage(app_state: TeaReadOnlySignal<AppState>) -> Element {
+        // A presentational component only gets a read-only view, so it cannot send actions
         let app_state_r = app_state.read();
+        let message = app_state_r.status.to_string();
+        let kettle = app_state_r.kettle.to_string();
+
+        rsx! {
+            div { "{message}" }
+            div { "{kettle}" }
+        }
+    }
+
+    /// Which of the options are offered, derived from the state for [`TeaOptions`].
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub(super) struct ShownOptions {
+        pub(super) show_bags: bool,
+        pub(super) show_water: bool,
+        pub(super) show_retry: bool,
+    }
+
+    impl ShownOptions {
+        pub(super) fn for_state(state: &AppState) -> Self {
+            Self {
+                show_bags: matches!(state.status, Status::EmptyCup),
+                show_water: matches!(state.status, Status::TeaBag(_)),
+                show_retry: matches!(state.status, Status::Error(_) | Status::TeaReady),
+            }
+        }
+    }
+
+    #[component]
+    fn TeaOptions(app_state: TeaModelSignal<AppState>, shown: ShownOptions) -> Element {
+        // Only the status is needed for the label, so the lens keeps the kettle from re-rendering the options
+        let status = use_hook(|| app_state.status_signal());
+        let retry = if *status.read() == Status::TeaReady {
+            "Another cup"
+        } else {
+            "Try again"
+        };
 
         rsx! {
-            if matches!(app_state_r.status, Status::EmptyCup) {
+            if shown.show_bags {
                 div {
                     class: "tea-options",
                         button {
-                            onclick: move |_| app_state.send(AppStatusUpdate::AddTeaBag(TeaType::Black)),
+                            onclick: move |_| app_state.add_tea_bag(TeaType::Black),
                             "Add Black Tea Bag"
                         }
                         button {
-                            onclick: move |_| app_state.send(AppStatusUpdate::AddTeaBag(TeaType::Green)),
+                            onclick: move |_| app_state.add_tea_bag(TeaType::Green),
                             "Add Green Tea Bag"
                         }
                         button {
-                            onclick: move |_| app_state.send(AppStatusUpdate::AddTeaBag(TeaType::White)),
+                            onclick: move |_| app_state.add_tea_bag(TeaType::White),
                             "Add White Tea Bag"
                         }
                         button {
-                            onclick: move |_| app_state.send(AppStatusUpdate::AddTeaBag(TeaType::Oolong)),
+                            onclick: move |_| app_state.add_tea_bag(TeaType::Oolong),
                             "Add Oolong Tea Bag"
                         }
                     }
-            } else if matches!(app_state_r.status, Status::TeaBag(_)) {
+            } else if shown.show_water {
                 div {
                     class: "tea-options",
                     button {
-                        onclick: move |_| app_state.send(AppStatusUpdate::AddWater(100)),
+                        onclick: move |_| app_state.kettle(KettleAction::Heat(100)),
+                        "Boil the kettle"
+                    }
+                    button {
+                        onclick: move |_| app_state.add_water(100),
                         "Add Water (100°C)"
                     }
                     button {
-                        onclick: move |_| app_state.send(AppStatusUpdate::AddWater(90)),
+                        onclick: move |_| app_state.add_water(90),
                         "Add Water (90°C)"
                     }
                     button {
-                        onclick: move |_| app_state.send(AppStatusUpdate::AddWater(80)),
+                        onclick: move |_| app_state.add_water(80),
                         "Add Water (80°C)"
                     }
                     button {
-                        onclick: move |_| app_state.send(AppStatusUpdate::AddWater(70)),
+                        onclick: move |_| app_state.add_water(70),
                         "Add Water (70°C)"
                     }
                 }
-            } else if matches!(app_state_r.status, Status::Error(_) | Status::TeaReady) {
+            } else if shown.show_retry {
                 div {
                     class: "tea-options",
                     button {
-                        onclick: move |_| app_state.send(AppStatusUpdate::CupFetched),
-                        "Try again"
+                        onclick: move |_| app_state.fetch_cup(),
+                        "{retry}"
                     }
                 }
             }
@@ -287,7 +533,18 @@ mod rsx_components {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::TeaType;
+    use crate::{
+        model::{SettingsChanged, TeaType},
+        rsx_components::ShownOptions,
+    };
+    use dioxus::dioxus_core::NoOpMutations;
+    #[cfg(feature = "serde")]
+    use dioxus_tea::use_tea_model_with;
+    use dioxus_tea::{
+        testing::TeaTestHarness, use_tea_bus, use_tea_model, use_tea_model_recorded, use_tea_model_with_history,
+        TeaRecorder,
+    };
+    use std::pin::pin;
 
     // We can use standard Rust unit tests to verify the functionality of our tea-making application.
 
@@ -308,6 +565,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn every_illegal_transition_is_rejected() {
+        let in_status = |status| AppState {
+            status,
+            ..AppState::default()
+        };
+        let illegal = [
+            (Status::EmptyCup, AppStatusUpdate::CupFetched),
+            (Status::FetchingCup, AppStatusUpdate::AddTeaBag(TeaType::Green)),
+            (
+                Status::TeaBag(TeaType::Green),
+                AppStatusUpdate::AddTeaBag(TeaType::Green),
+            ),
+            (Status::EmptyCup, AppStatusUpdate::AddWater(80)),
+            (Status::Water(80), AppStatusUpdate::AddWater(80)),
+            (Status::TeaBag(TeaType::Green), AppStatusUpdate::Done),
+            (Status::TeaReady, AppStatusUpdate::Done),
+        ];
+        for (status, action) in illegal {
+            assert!(!AppState::allowed(&in_status(status.clone()), &action), "{status:?}");
+        }
+
+        assert!(AppState::allowed(
+            &in_status(Status::TeaBag(TeaType::Green)),
+            &AppStatusUpdate::AddWater(80)
+        ));
+        assert!(
+            AppState::allowed(&in_status(Status::TeaReady), &AppStatusUpdate::FetchCup),
+            "A new cup can always be fetched"
+        );
+    }
+
+    #[test]
+    fn rejected_tea_bags_are_counted() {
+        let mut app_state = AppState::default();
+        app_state.update(AppStatusUpdate::AddTeaBag(TeaType::Green));
+        app_state.update(AppStatusUpdate::CupFetched);
+        app_state.update(AppStatusUpdate::AddTeaBag(TeaType::Green));
+        app_state.update(AppStatusUpdate::AddTeaBag(TeaType::Black));
+        assert_eq!(
+            REJECTED_TEA_BAGS.get(),
+            2,
+            "Only the tea bags without an empty cup are rejected"
+        );
+    }
+
     #[test]
     fn should_be_able_to_add_a_tea_bag_when_we_have_a_cup() {
         let mut app_state = AppState::default();
@@ -319,4 +622,225 @@ mod tests {
             "Should be able to add a tea bag after fetching a cup"
         );
     }
+
+    #[test]
+    fn the_options_only_depend_on_the_status() {
+        let mut app_state = AppState::default();
+        app_state.update(AppStatusUpdate::CupFetched);
+        let shown = ShownOptions::for_state(&app_state);
+        assert!(shown.show_bags && !shown.show_water && !shown.show_retry);
+
+        app_state.update(AppStatusUpdate::Kettle(KettleAction::Heat(100)));
+        assert_eq!(
+            ShownOptions::for_state(&app_state),
+            shown,
+            "Heating the kettle leaves the options as they are"
+        );
+    }
+
+    /// Mounts an app that creates a model with `hook` and provides it as context, and returns the virtual dom
+    /// together with the model.
+    fn mount_model<M: Clone + 'static>(hook: fn() -> M) -> (VirtualDom, M) {
+        fn app<M: Clone + 'static>(hook: fn() -> M) -> Element {
+            let model = hook();
+            use_context_provider(|| model);
+            rsx! {}
+        }
+
+        let mut dom = VirtualDom::new_with_props(app::<M>, hook);
+        dom.rebuild_in_place();
+        let model = dom.in_scope(ScopeId::APP, consume_context::<M>);
+        (dom, model)
+    }
+
+    #[test]
+    fn should_be_able_to_undo_back_to_fetching_a_cup() {
+        let (dom, app_state) = mount_model(|| use_tea_model_with_history::<AppState>(10));
+
+        dom.in_runtime(|| {
+            app_state.send_sync(AppStatusUpdate::CupFetched);
+            app_state.send_sync(AppStatusUpdate::AddTeaBag(TeaType::Black));
+            app_state.send_sync(AppStatusUpdate::AddTeaBag(TeaType::Green));
+            assert_eq!(app_state.peek().status, Status::Error(MakeTeaError::CupNotEmpty));
+
+            app_state.undo();
+            assert_eq!(app_state.peek().status, Status::TeaBag(TeaType::Black));
+            app_state.undo();
+            app_state.undo();
+            assert_eq!(app_state.peek().status, Status::FetchingCup);
+            assert!(!app_state.can_undo(), "There is nothing left to undo");
+
+            app_state.redo();
+            assert_eq!(app_state.peek().status, Status::EmptyCup);
+            assert!(app_state.can_redo());
+        });
+    }
+
+    #[tokio::test]
+    async fn dispatch_resolves_once_the_action_is_applied() {
+        let (mut dom, app_state) = mount_model(use_tea_model::<AppState>);
+
+        let mut dispatched = pin!(dom.in_runtime(|| app_state.dispatch(AppStatusUpdate::CupFetched)));
+        loop {
+            tokio::select! {
+                () = &mut dispatched => break,
+                () = dom.wait_for_work() => dom.render_immediate(&mut NoOpMutations),
+            }
+        }
+
+        assert_eq!(dom.in_runtime(|| app_state.peek().status.clone()), Status::EmptyCup);
+    }
+
+    #[tokio::test]
+    async fn changed_settings_put_a_fresh_cup_on_the_table() {
+        let (mut dom, (app_state, settings)) = mount_model(|| {
+            let app_state = use_tea_model::<AppState>();
+            let settings = use_tea_bus::<SettingsChanged>();
+            use_hook(|| app_state.connect_bus(settings, |_: &SettingsChanged| Some(AppStatusUpdate::CupFetched)));
+            (app_state, settings)
+        });
+
+        let mut changed = pin!(dom.in_runtime(|| {
+            let changed = app_state.next_change();
+            settings.publish(SettingsChanged);
+            changed
+        }));
+        loop {
+            tokio::select! {
+                state = &mut changed => {
+                    assert_eq!(state.status, Status::EmptyCup);
+                    break;
+                }
+                () = dom.wait_for_work() => dom.render_immediate(&mut NoOpMutations),
+            }
+        }
+    }
+
+    #[test]
+    fn a_recorded_session_replays_to_the_same_state() {
+        let (dom, app_state) = mount_model(use_tea_model_recorded::<AppState>);
+
+        dom.in_runtime(|| {
+            app_state.send_sync(AppStatusUpdate::CupFetched);
+            app_state.send_sync(AppStatusUpdate::AddTeaBag(TeaType::Black));
+            app_state.send_sync(AppStatusUpdate::AddWater(100));
+            app_state.send_sync(AppStatusUpdate::Done);
+            assert_eq!(app_state.peek().status, Status::TeaReady);
+
+            let replayed = TeaRecorder::<AppState>::replay(&app_state.recorded());
+            assert_eq!(replayed.status, Status::TeaReady);
+        });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn state_survives_a_json_round_trip() {
+        let (dom, app_state) = mount_model(|| {
+            use_tea_model_with(|| AppState {
+                status: Status::Water(80),
+                ..AppState::default()
+            })
+        });
+
+        dom.in_runtime(|| {
+            let snapshot = app_state.snapshot_json();
+            app_state.send_sync(AppStatusUpdate::CupFetched);
+            assert_eq!(app_state.peek().status, Status::EmptyCup);
+
+            let version = app_state.version();
+            app_state
+                .restore_json(&snapshot)
+                .expect("snapshot should be restorable");
+            assert_eq!(app_state.peek().status, Status::Water(80));
+            assert_eq!(app_state.version(), version + 1, "Restoring is a write like any other");
+        });
+    }
+
+    #[test]
+    fn should_start_brewing_after_adding_water_at_the_right_temperature() {
+        let mut app_state = AppState::default();
+        app_state.update(AppStatusUpdate::CupFetched);
+        app_state.update(AppStatusUpdate::AddTeaBag(TeaType::Green));
+        let cmd = app_state.update(AppStatusUpdate::AddWater(75));
+        assert_eq!(app_state.status, Status::Water(75));
+        assert!(!cmd.is_none(), "Brewing should schedule the Done action");
+    }
+
+    #[test]
+    fn a_whole_cup_of_tea_can_be_walked_through_with_the_harness() {
+        let mut app_state = TeaTestHarness::<AppState>::init();
+        app_state.apply(AppStatusUpdate::FetchCup);
+        assert_eq!(app_state.pending_effects(), 1, "Fetching the cup takes a while");
+
+        // the commands sleep on browser timers, so instead of running them, their actions are applied by hand
+        for action in [
+            AppStatusUpdate::CupFetched,
+            AppStatusUpdate::AddTeaBag(TeaType::Green),
+            AppStatusUpdate::AddWater(75),
+            AppStatusUpdate::Done,
+        ] {
+            app_state.apply(action);
+        }
+        app_state.assert_state(|state| state.status == Status::TeaReady);
+    }
+
+    #[test]
+    fn tea_is_never_ready_without_water() {
+        let actions = [
+            AppStatusUpdate::FetchCup,
+            AppStatusUpdate::CupFetched,
+            AppStatusUpdate::AddTeaBag(TeaType::Green),
+            AppStatusUpdate::AddWater(75),
+            AppStatusUpdate::Done,
+        ];
+
+        // every sequence of up to four actions, a poor man's property test
+        let mut sequences = vec![Vec::new()];
+        for length in 0..4 {
+            let longer: Vec<Vec<AppStatusUpdate>> = sequences
+                .iter()
+                .filter(|sequence| sequence.len() == length)
+                .flat_map(|sequence| {
+                    actions.iter().map(move |action| {
+                        let mut sequence = sequence.clone();
+                        sequence.push(action.clone());
+                        sequence
+                    })
+                })
+                .collect();
+            sequences.extend(longer);
+        }
+
+        for sequence in sequences {
+            let poured = sequence
+                .iter()
+                .any(|action| matches!(action, AppStatusUpdate::AddWater(_)));
+            let state = AppState::apply_all(AppState::default(), sequence);
+            assert!(poured || state.status != Status::TeaReady);
+        }
+    }
+
+    #[test]
+    fn trying_again_cancels_the_brewing_tea() {
+        assert!(AppState::cancels_effects(&AppStatusUpdate::FetchCup));
+        assert!(!AppState::cancels_effects(&AppStatusUpdate::AddWater(80)));
+    }
+
+    #[test]
+    fn kettle_actions_are_delegated_to_the_kettle() {
+        let mut app_state = AppState::default();
+        let cmd = app_state.update(AppStatusUpdate::Kettle(KettleAction::Heat(90)));
+        assert_eq!(
+            app_state.kettle,
+            KettleState {
+                temperature: 90,
+                heating: true
+            }
+        );
+        assert!(!cmd.is_none(), "The kettle reports back once the water is heated");
+
+        app_state.update(AppStatusUpdate::Kettle(KettleAction::Heated));
+        assert!(!app_state.kettle.heating);
+        assert_eq!(app_state.status, Status::FetchingCup, "The kettle leaves the cup alone");
+    }
 }
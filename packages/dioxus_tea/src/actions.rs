@@ -0,0 +1,86 @@
+//! A stream of the actions processed by TEA models, e.g. for telemetry.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::prelude::{ReadableExt, WritableExt};
+use futures_channel::mpsc;
+use futures_util::Stream;
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Returns a stream that yields a clone of every action the model processes, right before it reaches `update`.
+    ///
+    /// Any number of streams can be taken; each of them gets every action, in processing order, including the
+    /// actions produced by commands and the ones of a batch. This allows wiring up telemetry, e.g. counting how
+    /// often water is added, without touching the reducer. The stream ends when the model is dropped.
+    pub fn action_stream(&self) -> impl Stream<Item = T::Action>
+    where
+        T::Action: Clone,
+    {
+        let (tx, rx) = mpsc::unbounded();
+        let mut observers = self.observers;
+        observers.write().push(Box::new(move |action: &T::Action, _source| {
+            tx.unbounded_send(action.clone()).is_ok()
+        }));
+        rx
+    }
+
+    /// Passes `action` on to every stream returned by [`action_stream`](Self::action_stream), dropping the streams
+    /// that are no longer polled.
+    pub(crate) fn observe(&self, action: &T::Action) {
+        if self.observers.peek().is_empty() {
+            return;
+        }
+        let mut observers = self.observers;
+        let source = crate::source::current();
+        observers.write().retain(|observe| observe(action, source));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+    use futures_util::StreamExt;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Cup {
+        water: u8,
+    }
+
+    #[derive(Clone, PartialEq, Debug)]
+    enum CupAction {
+        AddWater(u8),
+        Spill,
+    }
+
+    impl TeaModel for Cup {
+        type Action = CupAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                CupAction::AddWater(water) => self.water += water,
+                CupAction::Spill => self.water = 0,
+            }
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn every_subscriber_sees_every_action() {
+        let (mut dom, cup) = mount_model(use_tea_model::<Cup>);
+        let (first, second) = dom.in_runtime(|| {
+            let streams = (cup.action_stream(), cup.action_stream());
+            cup.send(CupAction::AddWater(1));
+            cup.send_batch([CupAction::AddWater(2), CupAction::Spill]);
+            streams
+        });
+        run(&mut dom).await;
+        drop(dom);
+
+        let expected = [CupAction::AddWater(1), CupAction::AddWater(2), CupAction::Spill];
+        assert_eq!(first.collect::<Vec<_>>().await, expected);
+        assert_eq!(second.collect::<Vec<_>>().await, expected);
+    }
+}
@@ -0,0 +1,133 @@
+//! TEA models whose updates are asynchronous.
+
+use crate::TeaModelInit;
+use dioxus::{
+    dioxus_core::{current_scope_id, Runtime},
+    prelude::{use_hook, CopyValue, ReadableExt, ReadableRef, Signal, WritableExt},
+};
+use futures_channel::mpsc::{self, UnboundedSender};
+use futures_util::StreamExt;
+
+/// Variant of [`TeaModel`](crate::TeaModel) whose `update` can `await`, e.g. to fetch the cup from a server as part
+/// of the transition itself.
+///
+/// Updates are awaited one after the other: a slow update holds up every action sent after it, until it completes.
+/// Prefer a [`TeaModel`](crate::TeaModel) with a [`Cmd`](crate::Cmd) when the rest of the model should keep
+/// responding while the work is in flight, and reach for this trait when the next action must not be applied before
+/// the asynchronous work is done, e.g. because it depends on its outcome.
+#[allow(async_fn_in_trait)]
+pub trait AsyncTeaModel: 'static + Clone + PartialEq {
+    /// The type of actions that can be processed by this model.
+    type Action;
+
+    /// Updates the model state based on the provided action.
+    async fn update(&mut self, action: Self::Action);
+}
+
+/// A signal that holds the state of an [`AsyncTeaModel`] and provides an internal coroutine that awaits its updates.
+#[derive(Clone, PartialEq)]
+pub struct AsyncTeaModelSignal<T: AsyncTeaModel> {
+    inner: Signal<T>,
+    tx: CopyValue<UnboundedSender<<T as AsyncTeaModel>::Action>>,
+}
+
+impl<T: AsyncTeaModel> Copy for AsyncTeaModelSignal<T> {}
+
+impl<T: AsyncTeaModel> AsyncTeaModelSignal<T> {
+    #[must_use]
+    /// Returns a reference to the underlying signal for reading the model state.
+    pub fn read(&self) -> ReadableRef<'_, Signal<T>> {
+        self.inner.read()
+    }
+
+    #[must_use]
+    /// Returns a reference to the model state without subscribing the current component to changes.
+    pub fn peek(&self) -> ReadableRef<'_, Signal<T>> {
+        self.inner.peek()
+    }
+
+    /// Sends an action to the coroutine, to be processed once the updates of the actions before it completed.
+    pub fn send(&self, action: T::Action) {
+        let _ = self.tx.peek().unbounded_send(action);
+    }
+}
+
+#[must_use]
+/// Creates a new `AsyncTeaModelSignal` for the given `AsyncTeaModel`.
+///
+/// Every update works on a copy of the state, which is written back to the signal once the update completed, and
+/// only if it changed it. Components keep rendering the previous state while an update is in flight.
+pub fn use_async_tea_model<T: AsyncTeaModel + TeaModelInit>() -> AsyncTeaModelSignal<T> {
+    use_hook(|| {
+        let (tx, mut rx) = mpsc::unbounded::<T::Action>();
+        let mut inner = Signal::new(T::init());
+        Runtime::current().spawn(current_scope_id(), async move {
+            while let Some(action) = rx.next().await {
+                let mut state = inner.peek().clone();
+                state.update(action).await;
+                if state != *inner.peek() {
+                    inner.set(state);
+                }
+            }
+        });
+
+        AsyncTeaModelSignal {
+            inner,
+            tx: CopyValue::new(tx),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{mount_model, run};
+    use std::time::Duration;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    enum Status {
+        #[default]
+        NoCup,
+        EmptyCup,
+        Water(u8),
+    }
+
+    enum Action {
+        FetchCup,
+        AddWater(u8),
+    }
+
+    impl AsyncTeaModel for Status {
+        type Action = Action;
+
+        async fn update(&mut self, action: Self::Action) {
+            match action {
+                Action::FetchCup => {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    *self = Status::EmptyCup;
+                }
+                Action::AddWater(temperature) => {
+                    if *self == Status::EmptyCup {
+                        *self = Status::Water(temperature);
+                    }
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn updates_are_awaited_one_after_the_other() {
+        let (mut dom, status) = mount_model(use_async_tea_model::<Status>);
+        dom.in_runtime(|| {
+            status.send(Action::FetchCup);
+            status.send(Action::AddWater(80));
+        });
+        run(&mut dom).await;
+
+        assert_eq!(
+            dom.in_runtime(|| status.peek().clone()),
+            Status::Water(80),
+            "The water is only added once the cup has been fetched"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! TEA models with a bounded action queue, to apply backpressure to producers.
+
+use crate::{
+    use_tea_model_configured, Envelope, TeaChannelConfig, TeaModel, TeaModelInit, TeaModelSignal, TeaOverflow,
+};
+use std::{error::Error, fmt};
+
+/// Error returned by [`TeaModelSignal::try_send`] when the queue of a bounded model is full.
+///
+/// The rejected action is handed back, so it can be retried or sent with [`TeaModelSignal::send_async`] instead.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<A>(pub A);
+
+impl<A> SendError<A> {
+    #[must_use]
+    /// Returns the action that could not be sent.
+    pub fn into_inner(self) -> A {
+        self.0
+    }
+}
+
+impl<A> fmt::Debug for SendError<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<A> fmt::Display for SendError<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the action queue is full")
+    }
+}
+
+impl<A> Error for SendError<A> {}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Sends an action to the coroutine, unless the queue of a bounded model is full.
+    ///
+    /// Models with the default, unbounded queue always accept the action.
+    ///
+    /// # Errors
+    ///
+    /// Returns the action wrapped in a [`SendError`] when the queue is full.
+    pub fn try_send(&self, action: T::Action) -> Result<(), SendError<T::Action>> {
+        self.enqueue(Envelope::Action(action))
+            .map_err(|envelope| match envelope {
+                Envelope::Action(action) => SendError(action),
+                Envelope::Batch(_) | Envelope::Then(..) | Envelope::From(..) | Envelope::Transaction(..) => {
+                    unreachable!("the action was queued on its own")
+                }
+            })
+    }
+
+    /// Sends an action to the coroutine, waiting for the queue of a bounded model to have room for it.
+    ///
+    /// Models with the default, unbounded queue accept the action right away. The action is dropped if the model's
+    /// component unmounts while waiting.
+    pub async fn send_async(&self, action: T::Action) {
+        self.enqueue_async(Envelope::Action(action)).await;
+    }
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel` whose queue holds at most `capacity` messages.
+///
+/// By default, actions are queued on an unbounded channel: sending never fails, but a producer that outpaces
+/// `update` grows the queue without limit. With a bounded queue, memory is capped, at the price of having to deal
+/// with a full queue: [`TeaModelSignal::send`] (and everything built on it, like debouncing) drops the action,
+/// [`TeaModelSignal::try_send`] hands it back, and [`TeaModelSignal::send_async`] waits for room. A batch counts
+/// as a single message, and a capacity of zero behaves like a capacity of one.
+///
+/// Actions produced by commands bypass the bound, so follow-up work is never lost. This is a shorthand for
+/// [`use_tea_model_configured`] with the [`Error`](TeaOverflow::Error) policy.
+pub fn use_tea_model_bounded<T: TeaModel + TeaModelInit>(capacity: usize) -> TeaModelSignal<T> {
+    use_tea_model_configured(TeaChannelConfig::bounded(capacity).overflow(TeaOverflow::Error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        Cmd,
+    };
+    use dioxus::prelude::*;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Tray {
+        cups: Vec<u8>,
+    }
+
+    impl TeaModel for Tray {
+        type Action = u8;
+
+        fn update(&mut self, cup: Self::Action) -> Cmd<Self::Action> {
+            self.cups.push(cup);
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn a_full_queue_hands_the_action_back() {
+        let (mut dom, tray) = mount_model(|| use_tea_model_bounded::<Tray>(2));
+        dom.in_runtime(|| {
+            assert_eq!(tray.try_send(1), Ok(()));
+            assert_eq!(tray.try_send(2), Ok(()));
+            assert_eq!(tray.try_send(3).map_err(SendError::into_inner), Err(3));
+        });
+        run(&mut dom).await;
+
+        dom.in_runtime(|| {
+            assert_eq!(tray.peek().cups, [1, 2]);
+            assert_eq!(
+                tray.try_send(3),
+                Ok(()),
+                "The queue has room again once it is worked off"
+            );
+        });
+        run(&mut dom).await;
+        assert_eq!(dom.in_runtime(|| tray.peek().cups.clone()), [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn send_async_waits_for_room() {
+        let (mut dom, tray) = mount_model(|| use_tea_model_bounded::<Tray>(2));
+        dom.in_scope(ScopeId::APP, || {
+            tray.send(1);
+            tray.send(2);
+            spawn(async move { tray.send_async(3).await });
+        });
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| tray.peek().cups.clone()), [1, 2, 3]);
+    }
+}
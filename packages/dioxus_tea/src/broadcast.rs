@@ -0,0 +1,74 @@
+//! TEA models whose actions are shared with the other tabs of the browser, enabled with the `broadcast` feature.
+
+use crate::{use_tea_model_synced_with, TeaConnection, TeaModel, TeaModelInit, TeaModelSignal, TeaTransport};
+use futures_channel::mpsc;
+use futures_util::{stream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::MessageEvent;
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, sharing its actions with every tab of the same origin
+/// that uses a model on the same `channel_name`.
+///
+/// This is [`use_tea_model_synced_with`] over a [`BroadcastChannel`], so actions travel between the tabs just like
+/// between the clients of a websocket: every tab applies the actions of the others without running their commands,
+/// and the order of the actions is only guaranteed per tab. When two tabs act at the same time, each of them applies
+/// its own action first. Keep the actions that tabs may send simultaneously commutative, like "add a cup" rather
+/// than "set the cups to 3", or only let the focused tab send actions that depend on the current state. A new tab
+/// does not catch up on the actions applied before it was opened, so share a state that every tab starts from, e.g.
+/// with [`use_tea_model_persisted`](crate::use_tea_model_persisted).
+///
+/// A `BroadcastChannel` never delivers a message to the channel that posted it, and received actions are not posted
+/// again, so actions do not echo between the tabs. In browsers without `BroadcastChannel`, the model simply stays
+/// local to its tab. Since the channel is a browser API, this is only available on `wasm32`; the channel name is
+/// only used on the first render.
+pub fn use_tea_model_broadcast<T>(channel_name: &str) -> TeaModelSignal<T>
+where
+    T: TeaModel + TeaModelInit,
+    T::Action: Serialize + DeserializeOwned,
+{
+    let name = channel_name.to_string();
+    use_tea_model_synced_with(move || BroadcastChannel::new(name))
+}
+
+/// A [`TeaTransport`] over the browser's `BroadcastChannel`, which connects the tabs of the same origin.
+pub struct BroadcastChannel {
+    name: String,
+}
+
+impl BroadcastChannel {
+    #[must_use]
+    /// Creates a transport on the channel called `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl TeaTransport for BroadcastChannel {
+    async fn connect(&mut self) -> Option<TeaConnection> {
+        let Ok(channel) = web_sys::BroadcastChannel::new(&self.name) else {
+            // the browser does not support the channel, so the model stays local, without retrying
+            return std::future::pending().await;
+        };
+
+        let (incoming, incoming_rx) = mpsc::unbounded();
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            if let Some(json) = event.data().as_string() {
+                let _ = incoming.unbounded_send(json);
+            }
+        });
+        channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        // the handler is owned by the channel from now on; once the model is dropped, it finds the stream closed
+        on_message.forget();
+
+        // the outgoing actions are posted while the model polls for incoming ones, which saves a task of their own
+        let (outgoing, outgoing_rx) = mpsc::unbounded::<String>();
+        let posted = outgoing_rx.filter_map(move |json| {
+            let _ = channel.post_message(&JsValue::from_str(&json));
+            std::future::ready(None)
+        });
+
+        Some(TeaConnection::new(outgoing, stream::select(incoming_rx, posted)))
+    }
+}
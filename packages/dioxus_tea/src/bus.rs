@@ -0,0 +1,176 @@
+//! A bus of typed events, through which independent TEA models react to each other without knowing each other.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::{
+    dioxus_core::{Runtime, ScopeId},
+    prelude::{use_hook, CopyValue, ReadableExt, WritableExt},
+};
+use std::rc::Rc;
+
+/// Delivers an event to a connected model, returning `false` once the model is gone.
+type Subscriber<E> = Rc<dyn Fn(&E) -> bool>;
+
+/// The app-wide bus for events of type `E`, as returned by [`use_tea_bus`].
+///
+/// Any code can [`publish`](Self::publish) an event, and every model that was connected with
+/// [`connect_bus`](TeaModelSignal::connect_bus) translates it into an action of its own, or ignores it. Neither side
+/// knows the other: a settings page publishes `SettingsChanged`, and whichever models care react to it. The bus is a
+/// `Copy` handle, so it can be moved into event handlers freely.
+pub struct TeaBus<E: 'static> {
+    subscribers: CopyValue<Vec<Subscriber<E>>>,
+}
+
+impl<E> Clone for TeaBus<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for TeaBus<E> {}
+
+impl<E> PartialEq for TeaBus<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.subscribers == other.subscribers
+    }
+}
+
+impl<E> TeaBus<E> {
+    #[allow(clippy::needless_pass_by_value)]
+    /// Publishes `event` to every connected model.
+    ///
+    /// The models receive the event in the order in which they were connected, and queue the action it maps to like
+    /// [`send`](TeaModelSignal::send) does: the actions are applied after the ones that are already queued for each
+    /// model, once `publish` has returned, and the events of a bus keep their order within every model. Models
+    /// that have been dropped are disconnected on the way.
+    pub fn publish(&self, event: E) {
+        // a model may publish or connect in turn, so the subscribers must not stay borrowed
+        let subscribers = self.subscribers.peek().clone();
+        let gone: Vec<_> = subscribers
+            .into_iter()
+            .filter(|subscriber| !subscriber(&event))
+            .collect();
+        if !gone.is_empty() {
+            let mut subscribers = self.subscribers;
+            subscribers
+                .write()
+                .retain(|subscriber| !gone.iter().any(|gone| Rc::ptr_eq(gone, subscriber)));
+        }
+    }
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Connects this model to `bus`, sending the action that `mapper` returns for every published event, if any.
+    ///
+    /// The model stays connected until it is dropped. Connect it once, e.g. in `use_hook`, since every call adds
+    /// another connection.
+    ///
+    /// Since the actions are queued, an event never reaches a model while it is still being published, but
+    /// models can still form a cycle: if `Settings` publishes an event whenever it changes, and a model that reacts
+    /// to it changes the settings in turn, they keep each other busy forever. Keep events flowing in one direction,
+    /// or only publish when something actually changed.
+    ///
+    /// ```rust,ignore
+    /// let bus = use_tea_bus::<SettingsChanged>();
+    /// use_hook(|| app_state.connect_bus(bus, |_: &SettingsChanged| Some(AppStatusUpdate::CupFetched)));
+    /// ```
+    pub fn connect_bus<E>(&self, bus: TeaBus<E>, mapper: impl Fn(&E) -> Option<T::Action> + 'static) {
+        let model = *self;
+        let subscriber: Subscriber<E> = Rc::new(move |event| {
+            if model.inner.try_peek().is_err() {
+                return false;
+            }
+            if let Some(action) = mapper(event) {
+                model.send(action);
+            }
+            true
+        });
+        let mut subscribers = bus.subscribers;
+        subscribers.write().push(subscriber);
+    }
+}
+
+#[must_use]
+/// Returns the app's [`TeaBus`] for events of type `E`, creating it on the first call.
+///
+/// There is one bus per type of event, stored in the root context, so every component that asks for the same type
+/// gets the same bus.
+pub fn use_tea_bus<E: 'static>() -> TeaBus<E> {
+    use_hook(|| {
+        let runtime = Runtime::current();
+        runtime.has_context(ScopeId::ROOT).unwrap_or_else(|| {
+            let bus = TeaBus {
+                subscribers: CopyValue::new_in_scope(Vec::new(), ScopeId::ROOT),
+            };
+            runtime.provide_context(ScopeId::ROOT, bus)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount, run},
+        use_tea_model, Cmd,
+    };
+    use dioxus::prelude::*;
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum SettingsChanged {
+        CupSize(u8),
+        Theme,
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Cup {
+        size: u8,
+        refills: u8,
+    }
+
+    impl TeaModel for Cup {
+        type Action = Option<u8>;
+
+        fn update(&mut self, size: Self::Action) -> Cmd<Self::Action> {
+            match size {
+                Some(size) => self.size = size,
+                None => self.refills += 1,
+            }
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn published_events_are_mapped_into_actions() {
+        #[derive(Clone, Copy)]
+        struct Cups(TeaModelSignal<Cup>, TeaModelSignal<Cup>, TeaBus<SettingsChanged>);
+
+        fn app() -> Element {
+            let (mine, yours) = (use_tea_model::<Cup>(), use_tea_model::<Cup>());
+            let bus = use_tea_bus::<SettingsChanged>();
+            use_hook(|| {
+                mine.connect_bus(bus, |event| match event {
+                    SettingsChanged::CupSize(size) => Some(Some(*size)),
+                    SettingsChanged::Theme => None,
+                });
+                yours.connect_bus(bus, |_| Some(None));
+            });
+            use_context_provider(|| Cups(mine, yours, bus));
+            rsx! {}
+        }
+
+        let (mut dom, Cups(mine, yours, bus)) = mount::<Cups>(app);
+        dom.in_runtime(|| {
+            mine.send(Some(1));
+            bus.publish(SettingsChanged::CupSize(3));
+            bus.publish(SettingsChanged::Theme);
+        });
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| mine.peek().clone()), Cup { size: 3, refills: 0 });
+        assert_eq!(
+            dom.in_runtime(|| yours.peek().refills),
+            2,
+            "Every event reaches every model"
+        );
+    }
+}
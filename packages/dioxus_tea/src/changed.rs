@@ -0,0 +1,32 @@
+//! Explicit change reports of reducers, which let the coroutine skip comparing and writing unchanged states.
+
+use std::cell::Cell;
+
+thread_local! {
+    /// Whether the updates that are currently being applied reported a change, if they reported anything.
+    static REPORTED: Cell<Option<Changed>> = const { Cell::new(None) };
+}
+
+/// Whether an update changed the state, as reported by [`TeaModel::update_checked`](crate::TeaModel::update_checked).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Changed {
+    /// The update may have changed the state.
+    Yes,
+    /// The update left the state exactly as it was.
+    No,
+}
+
+/// Reports the change of the update that is currently being applied.
+pub(crate) fn report(changed: Changed) {
+    REPORTED.with(|reported| reported.set(Some(changed)));
+}
+
+/// Runs `f`, returning its result along with the change its update reported; an update that reported nothing may
+/// have changed the state.
+pub(crate) fn collect<R>(f: impl FnOnce() -> R) -> (R, Changed) {
+    // an update that applies another model's action right away keeps its own report apart
+    let outer = REPORTED.replace(None);
+    let result = f();
+    let changed = REPORTED.replace(outer).unwrap_or(Changed::Yes);
+    (result, changed)
+}
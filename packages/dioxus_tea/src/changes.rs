@@ -0,0 +1,158 @@
+//! A stream of the state changes of TEA models, for interop with other async code.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::prelude::{ReadableExt, WritableExt};
+use futures_channel::{mpsc, oneshot};
+use futures_util::Stream;
+use std::future::Future;
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Returns a stream that yields a clone of the state every time it changes.
+    ///
+    /// The stream is lossless: it yields every state the model goes through, in order, even if the consumer is slow
+    /// to poll it, because the changed states are buffered until they are taken. A batch sent with
+    /// [`send_batch`](Self::send_batch) counts as a single change, and updates that leave the state equal are not
+    /// yielded. The current state is not yielded; only the changes after this call are. The stream ends when the
+    /// model is dropped.
+    pub fn changes(&self) -> impl Stream<Item = T> {
+        let (tx, rx) = mpsc::unbounded();
+        let mut changes = self.changes;
+        changes.write().push(tx);
+        rx
+    }
+
+    #[must_use = "the future does nothing unless it is awaited"]
+    /// Resolves with the state after the next action that changes it, whichever action that is.
+    ///
+    /// This is meant for "wait until the cup is ready" flows, like an animation that starts once the state moved
+    /// on. Like [`changes`](Self::changes), only the changes after this call count, and actions that leave the state
+    /// equal are skipped. Every caller gets a future of its own, so all of them resolve on the same change, each with
+    /// a clone of the state.
+    ///
+    /// # Panics
+    ///
+    /// The returned future panics if the model's component unmounts before the state changed.
+    pub fn next_change(&self) -> impl Future<Output = T> {
+        let (tx, rx) = oneshot::channel();
+        let mut next_changes = self.next_changes;
+        next_changes.write().push(tx);
+        async move { rx.await.expect("the model was dropped before its state changed") }
+    }
+
+    /// Sends a clone of the current state to every stream returned by [`changes`](Self::changes), dropping the
+    /// streams that are no longer polled, and resolves the futures of [`next_change`](Self::next_change).
+    pub(crate) fn notify_changes(&self) {
+        let (mut changes, mut next_changes) = (self.changes, self.next_changes);
+        let state = self.inner.peek();
+        changes
+            .write()
+            .retain(|change| change.unbounded_send(state.clone()).is_ok());
+        for next_change in next_changes.write().drain(..) {
+            let _ = next_change.send(state.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+    use futures_util::{FutureExt, StreamExt};
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Pot {
+        cups: u8,
+    }
+
+    impl TeaModel for Pot {
+        type Action = u8;
+
+        fn update(&mut self, cups: Self::Action) -> Cmd<Self::Action> {
+            self.cups = cups;
+            Cmd::none()
+        }
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Table {
+        cup: bool,
+    }
+
+    enum TableAction {
+        FetchCup,
+        CupFetched,
+    }
+
+    impl TeaModel for Table {
+        type Action = TableAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                // fetching takes a while, so the state does not change yet
+                TableAction::FetchCup => Cmd::perform(std::future::ready(()), |()| TableAction::CupFetched),
+                TableAction::CupFetched => {
+                    self.cup = true;
+                    Cmd::none()
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn every_waiter_sees_the_next_change() {
+        let (mut dom, table) = mount_model(use_tea_model::<Table>);
+        let (first, second) = dom.in_runtime(|| {
+            let waiters = (table.next_change(), table.next_change());
+            table.send(TableAction::FetchCup);
+            waiters
+        });
+        run(&mut dom).await;
+
+        assert_eq!(
+            first.now_or_never(),
+            Some(Table { cup: true }),
+            "Only CupFetched changed the state"
+        );
+        assert_eq!(second.now_or_never(), Some(Table { cup: true }));
+    }
+
+    #[tokio::test]
+    async fn direct_writes_resolve_the_next_change() {
+        let (dom, pot) = mount_model(use_tea_model::<Pot>);
+        let next = dom.in_runtime(|| {
+            let next = pot.next_change();
+            pot.reset_with(Pot { cups: 4 });
+            next
+        });
+        assert_eq!(next.now_or_never(), Some(Pot { cups: 4 }));
+    }
+
+    #[tokio::test]
+    async fn changes_yield_every_changed_state() {
+        let (mut dom, pot) = mount_model(use_tea_model::<Pot>);
+        let mut changes = dom.in_runtime(|| {
+            let changes = pot.changes();
+            for cups in [1, 1, 2, 3] {
+                pot.send(cups);
+            }
+            changes
+        });
+        run(&mut dom).await;
+
+        let cups: Vec<_> = [changes.next().await, changes.next().await, changes.next().await]
+            .into_iter()
+            .map(|pot| pot.map(|pot| pot.cups))
+            .collect();
+        assert_eq!(
+            cups,
+            [Some(1), Some(2), Some(3)],
+            "Pouring the same cup twice is not a change"
+        );
+
+        drop(dom);
+        assert_eq!(changes.next().await, None, "The stream ends with the model");
+    }
+}
@@ -0,0 +1,172 @@
+//! The clock that the timers of a TEA model wait on, which tests can replace with a manual one.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::prelude::{ReadableExt, WritableExt};
+use futures_channel::oneshot;
+use std::{cell::RefCell, future::Future, rc::Rc, time::Duration};
+
+/// The clock that the timers of a model wait on, set with [`TeaModelSignal::set_clock`].
+///
+/// Every model starts out on the [`system`](Self::system) clock, which sleeps for real. A
+/// [`manual`](Self::manual) clock never moves on its own: its time only passes when [`advance`](Self::advance) is
+/// called, which makes tests of scheduled actions fast and deterministic. Clones share the same time, so a test keeps
+/// one clone to advance and hands the other to the model.
+#[derive(Clone, Default)]
+pub struct TeaClock {
+    manual: Option<Rc<RefCell<ManualTime>>>,
+}
+
+/// The time of a manual clock, and the timers that wait on it.
+#[derive(Default)]
+struct ManualTime {
+    now: Duration,
+    timers: Vec<(Duration, oneshot::Sender<()>)>,
+}
+
+impl TeaClock {
+    #[must_use]
+    /// Returns the clock that sleeps for real, using the timers of `dioxus_sdk`.
+    pub fn system() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    /// Returns a clock that starts at zero and only moves when it is [advanced](Self::advance).
+    pub fn manual() -> Self {
+        Self {
+            manual: Some(Rc::default()),
+        }
+    }
+
+    /// Moves a manual clock forward by `by`, firing every timer that is due by then.
+    ///
+    /// The timers fire in the order of their deadlines, but the actions they send are only processed once the
+    /// runtime gets to poll the model again. A timer that is started by a fired one, like the next tick of an
+    /// interval, counts from the new time, so advance an interval one period at a time to see each of its ticks.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called on the [`system`](Self::system) clock, whose time cannot be controlled.
+    pub fn advance(&self, by: Duration) {
+        let time = self.manual.as_ref().expect("only a manual clock can be advanced");
+        let due = {
+            let mut time = time.borrow_mut();
+            time.now += by;
+            let now = time.now;
+            let (mut due, pending) = time
+                .timers
+                .drain(..)
+                .partition::<Vec<_>, _>(|(deadline, _)| *deadline <= now);
+            time.timers = pending;
+            due.sort_by_key(|(deadline, _)| *deadline);
+            due
+        };
+        for (_, timer) in due {
+            // the sleep may have been cancelled in the meantime, along with its task
+            let _ = timer.send(());
+        }
+    }
+
+    #[must_use]
+    /// Returns how far a manual clock has been advanced, or zero for the system clock.
+    pub fn elapsed(&self) -> Duration {
+        self.manual.as_ref().map_or(Duration::ZERO, |time| time.borrow().now)
+    }
+
+    /// Waits until `duration` has passed on this clock.
+    pub(crate) fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + 'static {
+        let manual = self.manual.clone();
+        async move {
+            let Some(time) = manual else {
+                return dioxus_sdk::time::sleep(duration).await;
+            };
+            if duration.is_zero() {
+                return;
+            }
+
+            let (timer, fired) = oneshot::channel();
+            {
+                let mut time = time.borrow_mut();
+                let deadline = time.now + duration;
+                time.timers.push((deadline, timer));
+            }
+            // the sender lives in the clock, which outlives the sleep, so this only returns once the timer fired
+            let _ = fired.await;
+        }
+    }
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Replaces the clock that the timers of this model wait on, e.g. with a [manual](TeaClock::manual) one in tests.
+    ///
+    /// The clock is used by [`send_after`](Self::send_after), [`send_interval`](Self::send_interval),
+    /// [`send_debounced`](Self::send_debounced), [`send_throttled`](Self::send_throttled),
+    /// [`use_tea_idle`](crate::use_tea_idle) and the expiry of [`use_tea_notifications`](crate::use_tea_notifications).
+    /// It applies to the timers started afterwards; the ones that are already running keep waiting on the previous
+    /// clock. Each model has a clock of its own, so the other models of the app keep running on real time.
+    pub fn set_clock(&self, clock: TeaClock) {
+        let mut current = self.clock;
+        current.set(clock);
+    }
+
+    /// Returns the clock that the timers of this model wait on.
+    pub(crate) fn clock(&self) -> TeaClock {
+        self.clock.peek().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Brew {
+        steeping: bool,
+        done: bool,
+    }
+
+    enum BrewAction {
+        Steep,
+        Done,
+    }
+
+    impl TeaModel for Brew {
+        type Action = BrewAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                BrewAction::Steep => self.steeping = true,
+                BrewAction::Done => {
+                    self.steeping = false;
+                    self.done = true;
+                }
+            }
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn advancing_a_manual_clock_fires_the_scheduled_actions() {
+        let (mut dom, brew) = mount_model(use_tea_model::<Brew>);
+        let clock = TeaClock::manual();
+        dom.in_runtime(|| {
+            brew.set_clock(clock.clone());
+            brew.send(BrewAction::Steep);
+            brew.send_after(BrewAction::Done, Duration::from_secs(180));
+        });
+        run(&mut dom).await;
+
+        clock.advance(Duration::from_secs(179));
+        run(&mut dom).await;
+        assert!(dom.in_runtime(|| brew.peek().steeping), "The tea is still steeping");
+
+        clock.advance(Duration::from_secs(1));
+        run(&mut dom).await;
+        assert!(dom.in_runtime(|| brew.peek().done));
+        assert_eq!(clock.elapsed(), Duration::from_secs(180));
+    }
+}
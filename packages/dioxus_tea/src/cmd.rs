@@ -0,0 +1,193 @@
+//! Commands that can be returned from [`TeaModel::update`](crate::TeaModel::update),
+//! modelled after Elm's [`Cmd`](https://package.elm-lang.org/packages/elm/core/latest/Platform-Cmd).
+
+use dioxus::{
+    dioxus_core::{Runtime, ScopeId, Task},
+    prelude::{CopyValue, WritableExt},
+};
+use futures_util::{future::LocalBoxFuture, FutureExt};
+use std::{collections::HashMap, future::Future};
+
+/// Asynchronous work to run after the model has been updated, producing zero or more follow-up actions.
+///
+/// Commands are spawned by the model's coroutine once the state change has been applied, so they do not block the
+/// processing of subsequent actions. Every action produced by a command is sent back into the model, just as if it
+/// was passed to [`TeaModelSignal::send`](crate::TeaModelSignal::send).
+///
+/// Every command runs as a task of its own, so the effects of many actions are in flight at the same time; only the
+/// synchronous `update` calls are applied one after the other. That is why a model has a single coroutine: the
+/// Dioxus runtime polls all tasks on one thread, so several coroutines pulling from the same queue would not apply
+/// updates in parallel, they would merely give up the FIFO order. Updates that are too expensive for the UI thread
+/// belong on [`use_tea_model_threaded`](crate::use_tea_model_threaded) or
+/// [`use_tea_model_on`](crate::use_tea_model_on) instead.
+pub struct Cmd<A> {
+    effects: Vec<LocalBoxFuture<'static, Option<A>>>,
+    /// Follow-up actions that are applied right after the current one, see [`Cmd::dispatch_next`].
+    next: Vec<A>,
+}
+
+impl<A: 'static> Cmd<A> {
+    #[must_use]
+    /// Creates a command that does nothing.
+    pub fn none() -> Self {
+        Self {
+            effects: Vec::new(),
+            next: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    /// Creates a command that applies `action` right after the current one, without running any effect.
+    ///
+    /// This is meant for synchronous chains, like `AddWater` going on to `Done` once the water is hot enough, which
+    /// would otherwise take a [`perform`](Self::perform) of a ready future and a trip through the queue. The follow-up
+    /// takes precedence over the actions that are already queued: it is applied before the next message is taken from
+    /// the queue, within the same write of the state as the action that dispatched it. Several follow-ups, e.g. from
+    /// a [`batch`](Self::batch), are applied in the order they were dispatched, and their own follow-ups after them.
+    ///
+    /// A follow-up that always dispatches another one never lets the model get to its queue again, so make sure the
+    /// chain ends.
+    pub fn dispatch_next(action: A) -> Self {
+        Self {
+            effects: Vec::new(),
+            next: vec![action],
+        }
+    }
+
+    #[must_use]
+    /// Creates a command that awaits the future and maps its output into a follow-up action.
+    pub fn perform<F, M>(future: F, map: M) -> Self
+    where
+        F: Future + 'static,
+        M: FnOnce(F::Output) -> A + 'static,
+    {
+        Self {
+            effects: vec![future.map(|output| Some(map(output))).boxed_local()],
+            next: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    /// Combines several commands into one; all of them run concurrently.
+    pub fn batch(cmds: impl IntoIterator<Item = Cmd<A>>) -> Self {
+        let mut batch = Self::none();
+        for cmd in cmds {
+            batch.effects.extend(cmd.effects);
+            batch.next.extend(cmd.next);
+        }
+        batch
+    }
+
+    #[must_use]
+    /// Transforms the actions produced by this command.
+    ///
+    /// This lifts the commands of a child model into the action type of its parent, Elm-style:
+    ///
+    /// ```rust,ignore
+    /// AppStatusUpdate::Kettle(action) => self.kettle.update(action).map(AppStatusUpdate::Kettle),
+    /// ```
+    pub fn map<B: 'static>(self, f: impl Fn(A) -> B + Clone + 'static) -> Cmd<B> {
+        Cmd {
+            effects: self
+                .effects
+                .into_iter()
+                .map(|effect| {
+                    let f = f.clone();
+                    effect.map(move |action| action.map(f)).boxed_local()
+                })
+                .collect(),
+            next: self.next.into_iter().map(f).collect(),
+        }
+    }
+
+    #[must_use]
+    /// Returns `true` if the command does not run any work, nor dispatch any follow-up.
+    pub fn is_none(&self) -> bool {
+        self.effects.is_empty() && self.next.is_empty()
+    }
+
+    /// Takes the follow-up actions out of this command, for applying them right away.
+    pub(crate) fn take_next(&mut self) -> Vec<A> {
+        std::mem::take(&mut self.next)
+    }
+
+    #[must_use]
+    /// Returns the futures of this command, for running them outside of the Dioxus runtime, e.g. in a custom driver
+    /// built on [`process_action`](crate::process_action).
+    ///
+    /// Each future resolves to the action to apply next, if any. Follow-ups of [`dispatch_next`](Self::dispatch_next)
+    /// are not included, as `process_action` has already applied them.
+    pub fn into_effects(self) -> Vec<LocalBoxFuture<'static, Option<A>>> {
+        self.effects
+    }
+
+    /// Spawns every effect of this command in `scope`, passing the resulting actions to `send`.
+    ///
+    /// The effects are tracked in `running` until they complete, so they can be cancelled.
+    pub(crate) fn spawn(self, scope: ScopeId, mut running: CopyValue<Running>, send: impl Fn(A) + Clone + 'static) {
+        let runtime = Runtime::current();
+        // the coroutine takes the follow-ups beforehand, so these are only the ones of a command spawned directly
+        for action in self.next {
+            send(action);
+        }
+        for effect in self.effects {
+            let send = send.clone();
+            let id = running.write().next_id();
+            let task = runtime.spawn(scope, async move {
+                let action = effect.await;
+                running.write().tasks.remove(&id);
+                if let Some(action) = action {
+                    send(action);
+                }
+            });
+            running.write().tasks.insert(id, task);
+        }
+    }
+}
+
+impl<A: 'static> Default for Cmd<A> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// Conversion into a [`Cmd`], so handlers can return either `()`, for no follow-up work, or a command.
+///
+/// This is what allows the handlers of a derived `TeaReducer` to leave out the return type.
+pub trait IntoCmd<A> {
+    /// Converts the value into a command.
+    fn into_cmd(self) -> Cmd<A>;
+}
+
+impl<A: 'static> IntoCmd<A> for () {
+    fn into_cmd(self) -> Cmd<A> {
+        Cmd::none()
+    }
+}
+
+impl<A> IntoCmd<A> for Cmd<A> {
+    fn into_cmd(self) -> Cmd<A> {
+        self
+    }
+}
+
+/// The tasks of the commands of a model that are still running.
+#[derive(Default)]
+pub(crate) struct Running {
+    last_id: u64,
+    tasks: HashMap<u64, Task>,
+}
+
+impl Running {
+    fn next_id(&mut self) -> u64 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    /// Cancels every running command.
+    pub(crate) fn cancel_all(&mut self) {
+        for (_, task) in self.tasks.drain() {
+            task.cancel();
+        }
+    }
+}
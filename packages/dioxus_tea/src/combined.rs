@@ -0,0 +1,118 @@
+//! Composing two independent TEA models.
+
+use crate::{use_tea_model, TeaModel, TeaModelInit, TeaModelSignal};
+
+#[must_use]
+/// Creates a `TeaModelSignal` for each of the given `TeaModel`s.
+///
+/// The models are independent: each one has its own state and coroutine. Wrap them in a [`TeaCombined`] to provide
+/// both through a single context and to dispatch actions of either model with one `send`.
+pub fn use_tea_model_pair<A: TeaModel + TeaModelInit, B: TeaModel + TeaModelInit>(
+) -> (TeaModelSignal<A>, TeaModelSignal<B>) {
+    (use_tea_model(), use_tea_model())
+}
+
+/// An action for one of the models of a [`TeaCombined`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TeaCombinedAction<A, B> {
+    /// An action for the first model.
+    First(A),
+    /// An action for the second model.
+    Second(B),
+}
+
+/// Two models that are passed around, and sent actions to, as one.
+#[derive(Clone, PartialEq)]
+pub struct TeaCombined<A: TeaModel, B: TeaModel> {
+    first: TeaModelSignal<A>,
+    second: TeaModelSignal<B>,
+}
+
+impl<A: TeaModel, B: TeaModel> Copy for TeaCombined<A, B> {}
+
+impl<A: TeaModel, B: TeaModel> TeaCombined<A, B> {
+    #[must_use]
+    /// Combines the two models, e.g. as returned by [`use_tea_model_pair`].
+    pub fn new(first: TeaModelSignal<A>, second: TeaModelSignal<B>) -> Self {
+        Self { first, second }
+    }
+
+    #[must_use]
+    /// Returns the first model.
+    pub fn first(&self) -> TeaModelSignal<A> {
+        self.first
+    }
+
+    #[must_use]
+    /// Returns the second model.
+    pub fn second(&self) -> TeaModelSignal<B> {
+        self.second
+    }
+
+    /// Sends the action to the model it belongs to.
+    pub fn send(&self, action: TeaCombinedAction<A::Action, B::Action>) {
+        match action {
+            TeaCombinedAction::First(action) => self.first.send(action),
+            TeaCombinedAction::Second(action) => self.second.send(action),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount, run},
+        Cmd,
+    };
+    use dioxus::prelude::*;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Tea {
+        cups: u8,
+    }
+
+    impl TeaModel for Tea {
+        type Action = u8;
+
+        fn update(&mut self, cups: Self::Action) -> Cmd<Self::Action> {
+            self.cups += cups;
+            Cmd::none()
+        }
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Settings {
+        sugar: bool,
+    }
+
+    impl TeaModel for Settings {
+        type Action = bool;
+
+        fn update(&mut self, sugar: Self::Action) -> Cmd<Self::Action> {
+            self.sugar = sugar;
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn actions_are_routed_to_their_model() {
+        fn app() -> Element {
+            let (tea, settings) = use_tea_model_pair::<Tea, Settings>();
+            use_context_provider(|| TeaCombined::new(tea, settings));
+            rsx! {}
+        }
+
+        let (mut dom, models) = mount::<TeaCombined<Tea, Settings>>(app);
+        dom.in_runtime(|| {
+            models.send(TeaCombinedAction::First(2));
+            models.send(TeaCombinedAction::Second(true));
+        });
+        run(&mut dom).await;
+
+        dom.in_runtime(|| {
+            assert_eq!(models.first().peek().cups, 2);
+            assert!(models.second().peek().sugar);
+        });
+    }
+}
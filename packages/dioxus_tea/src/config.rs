@@ -0,0 +1,343 @@
+//! Tuning the action queue of TEA models in one place: its capacity, what happens when it is full, and its metrics.
+
+use crate::{apply, use_tea_model_queued, Envelope, Queue, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::prelude::ReadableExt;
+use std::{
+    collections::VecDeque,
+    task::{Context, Poll, Waker},
+};
+
+/// What happens to an action that is sent while the queue of a model from [`use_tea_model_configured`] is full.
+///
+/// The policy applies to the ways of sending that cannot wait, like [`send`](TeaModelSignal::send) and
+/// [`try_send`](TeaModelSignal::try_send). [`send_async`](TeaModelSignal::send_async) waits for room instead, and
+/// the actions of [`send_query`](TeaModelSignal::send_query) and [`dispatch`](TeaModelSignal::dispatch), whose callers
+/// wait for an answer, are always queued, past the capacity if need be, and so are
+/// [transactions](TeaModelSignal::transaction), which keep their place in the queue.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum TeaOverflow {
+    /// Nothing is lost: as the ways of sending that cannot wait must not block the UI thread either, they queue the
+    /// action past the capacity. The capacity then only holds back the callers of `send_async`.
+    #[default]
+    Block,
+    /// The oldest queued message is dropped to make room, so the queue always holds the most recent actions, in
+    /// order. This suits actions that supersede each other, like positions. Queued queries and transactions are
+    /// skipped, and the new action is queued past the capacity when there is nothing else to drop.
+    DropOldest,
+    /// The new action is dropped, so the queued ones are applied as they are, in order.
+    DropNewest,
+    /// The new action is dropped, like with `DropNewest`, but [`try_send`](TeaModelSignal::try_send) reports it by
+    /// handing the action back in a [`SendError`](crate::SendError).
+    Error,
+}
+
+/// The configuration of the action queue of a model, for [`use_tea_model_configured`].
+///
+/// The default is an unbounded queue without metrics, like the queue of [`use_tea_model`](crate::use_tea_model):
+///
+/// ```rust,ignore
+/// let config = TeaChannelConfig::bounded(16).overflow(TeaOverflow::DropOldest).with_metrics();
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct TeaChannelConfig {
+    capacity: Option<usize>,
+    overflow: TeaOverflow,
+    metrics: bool,
+}
+
+impl TeaChannelConfig {
+    #[must_use]
+    /// Returns the configuration of a queue that holds at most `capacity` messages; see [`overflow`](Self::overflow)
+    /// for what happens beyond that.
+    ///
+    /// Like for [`use_tea_model_bounded`](crate::use_tea_model_bounded), a batch counts as a single message, the
+    /// actions produced by commands bypass the bound, and a capacity of zero behaves like a capacity of one.
+    pub fn bounded(capacity: usize) -> Self {
+        Self {
+            capacity: Some(capacity.max(1)),
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    /// Sets what happens to an action that is sent while the queue is full, [`TeaOverflow::Block`] by default.
+    pub fn overflow(self, overflow: TeaOverflow) -> Self {
+        Self { overflow, ..self }
+    }
+
+    #[must_use]
+    /// Collects the [`TeaChannelMetrics`] of the queue, see [`TeaModelSignal::channel_metrics`].
+    pub fn with_metrics(self) -> Self {
+        Self { metrics: true, ..self }
+    }
+}
+
+/// Statistics on the action queue of a model, see [`TeaModelSignal::channel_metrics`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct TeaChannelMetrics {
+    /// Number of messages queued by components.
+    pub queued: u64,
+    /// Number of messages dropped, or handed back, because the queue was full.
+    pub dropped: u64,
+    /// The most messages that were waiting in the queue at once.
+    pub max_len: usize,
+}
+
+/// The action queue of a model from [`use_tea_model_configured`], which the coroutine takes its messages from.
+pub(crate) struct Channel<A> {
+    config: TeaChannelConfig,
+    messages: VecDeque<Envelope<A>>,
+    receiver: Option<Waker>,
+    /// Callers of `send_async` that wait for room.
+    senders: Vec<Waker>,
+    closed: bool,
+    metrics: TeaChannelMetrics,
+}
+
+impl<A> Channel<A> {
+    pub(crate) fn new(config: TeaChannelConfig) -> Self {
+        Self {
+            config,
+            messages: VecDeque::new(),
+            receiver: None,
+            senders: Vec::new(),
+            closed: false,
+            metrics: TeaChannelMetrics::default(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.config
+            .capacity
+            .is_some_and(|capacity| self.messages.len() >= capacity)
+    }
+
+    /// Queues `envelope`, following the overflow policy when the queue is full.
+    ///
+    /// Returns the message that was dropped to make room, or the new message itself when it was dropped, and gives
+    /// the new message back when the policy is [`TeaOverflow::Error`].
+    pub(crate) fn push(&mut self, envelope: Envelope<A>) -> Result<Option<Envelope<A>>, Envelope<A>> {
+        if self.closed {
+            return Ok(Some(envelope));
+        }
+        let mut dropped = None;
+        // queries and transactions are always queued, as their callers rely on them being applied
+        if self.is_full() && !matches!(envelope, Envelope::Then(..) | Envelope::Transaction(..)) {
+            match self.config.overflow {
+                TeaOverflow::Block => {}
+                TeaOverflow::DropOldest => {
+                    // queries and transactions are never evicted, as their callers rely on them being applied
+                    let oldest = self.messages.iter().position(|message| {
+                        matches!(message, Envelope::Action(_) | Envelope::Batch(_) | Envelope::From(..))
+                    });
+                    if let Some(oldest) = oldest {
+                        self.count_dropped();
+                        dropped = self.messages.remove(oldest);
+                    }
+                }
+                TeaOverflow::DropNewest => {
+                    self.count_dropped();
+                    return Ok(Some(envelope));
+                }
+                TeaOverflow::Error => {
+                    self.count_dropped();
+                    return Err(envelope);
+                }
+            }
+        }
+
+        self.messages.push_back(envelope);
+        if self.config.metrics {
+            self.metrics.queued += 1;
+            self.metrics.max_len = self.metrics.max_len.max(self.messages.len());
+        }
+        if let Some(receiver) = self.receiver.take() {
+            receiver.wake();
+        }
+        Ok(dropped)
+    }
+
+    fn count_dropped(&mut self) {
+        if self.config.metrics {
+            self.metrics.dropped += 1;
+        }
+    }
+
+    /// Resolves once a caller of `send_async` may queue its message, i.e. once the queue has room.
+    pub(crate) fn poll_ready(&mut self, cx: &Context<'_>) -> Poll<()> {
+        if self.closed || !self.is_full() {
+            return Poll::Ready(());
+        }
+        self.senders.push(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Takes the next message for the coroutine, or `None` once the queue has been closed and worked off.
+    pub(crate) fn poll_next(&mut self, cx: &Context<'_>) -> Poll<Option<Envelope<A>>> {
+        if let Some(message) = self.messages.pop_front() {
+            for sender in self.senders.drain(..) {
+                sender.wake();
+            }
+            return Poll::Ready(Some(message));
+        }
+        if self.closed {
+            return Poll::Ready(None);
+        }
+        self.receiver = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Closes the queue: later messages are dropped, while the ones that are queued already are still taken.
+    pub(crate) fn close(&mut self) {
+        self.closed = true;
+        for waker in self.receiver.take().into_iter().chain(self.senders.drain(..)) {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    #[must_use]
+    /// Returns the statistics of the action queue of a model from [`use_tea_model_configured`], if its
+    /// configuration asked for them with [`with_metrics`](TeaChannelConfig::with_metrics).
+    ///
+    /// A `max_len` close to the capacity, or any `dropped` message, shows that producers outpace `update`. Reading the
+    /// metrics does not subscribe the current component.
+    pub fn channel_metrics(&self) -> Option<TeaChannelMetrics> {
+        let channel = self.channel.peek();
+        channel
+            .as_ref()
+            .filter(|channel| channel.config.metrics)
+            .map(|channel| channel.metrics)
+    }
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel` whose action queue is tuned by `config`.
+///
+/// This is the one entry point for everything about the queue: its capacity, the [`TeaOverflow`] policy for a full
+/// queue, and [`TeaChannelMetrics`]. All policies keep the order of the actions that are applied; they differ in which
+/// actions are lost. With [`Block`](TeaOverflow::Block), none are, [`DropOldest`](TeaOverflow::DropOldest) loses
+/// the oldest queued ones, and [`DropNewest`](TeaOverflow::DropNewest) and [`Error`](TeaOverflow::Error) the new
+/// ones that did not fit. Actions produced by commands bypass the bound, so follow-up work is never lost.
+///
+/// The configuration is only used on the first render.
+pub fn use_tea_model_configured<T: TeaModel + TeaModelInit>(config: TeaChannelConfig) -> TeaModelSignal<T> {
+    use_tea_model_queued(T::init, || Box::new(apply), Queue::Configured(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        Cmd, SendError,
+    };
+    use dioxus::prelude::*;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Tray {
+        cups: Vec<u8>,
+    }
+
+    impl TeaModel for Tray {
+        type Action = u8;
+
+        fn update(&mut self, cup: Self::Action) -> Cmd<Self::Action> {
+            self.cups.push(cup);
+            Cmd::none()
+        }
+    }
+
+    /// Fills a tray that holds two cups with four, and returns the cups that made it.
+    async fn overflow(overflow: TeaOverflow) -> (Vec<u8>, Option<TeaChannelMetrics>) {
+        fn app() -> Element {
+            let overflow = use_context::<TeaOverflow>();
+            let model =
+                use_tea_model_configured::<Tray>(TeaChannelConfig::bounded(2).overflow(overflow).with_metrics());
+            use_context_provider(|| model);
+            rsx! {}
+        }
+
+        let mut dom = VirtualDom::new(app).with_root_context(overflow);
+        dom.rebuild_in_place();
+        let tray = dom.in_scope(ScopeId::APP, consume_context::<TeaModelSignal<Tray>>);
+        dom.in_runtime(|| {
+            for cup in 1..=4 {
+                tray.send(cup);
+            }
+        });
+        run(&mut dom).await;
+        dom.in_runtime(|| (tray.peek().cups.clone(), tray.channel_metrics()))
+    }
+
+    #[tokio::test]
+    async fn full_queues_drop_the_oldest_or_the_newest_actions() {
+        let (cups, metrics) = overflow(TeaOverflow::DropOldest).await;
+        assert_eq!(cups, [3, 4], "The most recent cups push out the older ones");
+        assert_eq!(
+            metrics,
+            Some(TeaChannelMetrics {
+                queued: 4,
+                dropped: 2,
+                max_len: 2
+            })
+        );
+
+        let (cups, metrics) = overflow(TeaOverflow::DropNewest).await;
+        assert_eq!(cups, [1, 2], "The cups that did not fit are dropped");
+        assert_eq!(metrics.map(|metrics| metrics.dropped), Some(2));
+
+        let (cups, _) = overflow(TeaOverflow::Block).await;
+        assert_eq!(cups, [1, 2, 3, 4], "Nothing is lost");
+    }
+
+    #[tokio::test]
+    async fn erroring_queues_hand_the_action_back() {
+        let (mut dom, tray) =
+            mount_model(|| use_tea_model_configured::<Tray>(TeaChannelConfig::bounded(1).overflow(TeaOverflow::Error)));
+        dom.in_runtime(|| {
+            assert_eq!(tray.try_send(1), Ok(()));
+            assert_eq!(tray.try_send(2).map_err(SendError::into_inner), Err(2));
+            assert_eq!(tray.channel_metrics(), None, "Metrics are opt-in");
+        });
+        run(&mut dom).await;
+        assert_eq!(dom.in_runtime(|| tray.peek().cups.clone()), [1]);
+    }
+
+    #[tokio::test]
+    async fn queued_queries_are_never_dropped() {
+        let (mut dom, tray) = mount_model(|| {
+            use_tea_model_configured::<Tray>(TeaChannelConfig::bounded(1).overflow(TeaOverflow::DropOldest))
+        });
+        dom.in_scope(ScopeId::APP, || {
+            spawn(tray.dispatch(1));
+            tray.send(2);
+            tray.send(3);
+        });
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| tray.peek().cups.clone()),
+            [1, 3],
+            "The query is answered, and the plain action behind it makes room"
+        );
+    }
+
+    #[tokio::test]
+    async fn transactions_are_queued_past_a_full_queue() {
+        let (mut dom, tray) = mount_model(|| {
+            use_tea_model_configured::<Tray>(TeaChannelConfig::bounded(1).overflow(TeaOverflow::DropNewest))
+        });
+        dom.in_runtime(|| {
+            tray.send(1);
+            tray.transaction(vec![2, 3], |_| true);
+            tray.send(4);
+        });
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| tray.peek().cups.clone()),
+            [1, 2, 3],
+            "The transaction is applied, while the plain action behind it is dropped"
+        );
+    }
+}
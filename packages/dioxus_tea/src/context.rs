@@ -0,0 +1,149 @@
+//! Sharing TEA models with a subtree through context.
+
+use crate::{use_tea_model, use_tea_model_with, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::prelude::*;
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel` and provides it to the context of the current component.
+///
+/// This is a shorthand for `use_tea_model` followed by `use_context_provider`. Descendants fetch the model with
+/// [`use_tea_consumer`].
+pub fn use_tea_context<T: TeaModel + TeaModelInit>() -> TeaModelSignal<T> {
+    let model = use_tea_model::<T>();
+    use_context_provider(|| model)
+}
+
+#[must_use]
+/// Returns the `TeaModelSignal` provided by an ancestor, e.g. with [`use_tea_context`].
+///
+/// The context holds the `Copy` handle of the model rather than its state, so every consumer gets the live model:
+/// reading it always returns the current state, and actions sent through it reach the one coroutine. That is unlike
+/// providing the state itself, e.g. `use_context_provider(|| model.read().clone())`, which hands out a snapshot that
+/// goes stale with the first update.
+///
+/// # Panics
+///
+/// Panics if no ancestor provides a model of this type.
+pub fn use_tea_consumer<T: TeaModel>() -> TeaModelSignal<T> {
+    use_context::<TeaModelSignal<T>>()
+}
+
+/// Creates a model and provides it to its children, the declarative counterpart of [`use_tea_context`].
+///
+/// The model starts from `initial`, or from `T::init()` when it is omitted, and `on_init` runs once, right after
+/// the model has been created, e.g. to send a starting action. Like the initializer of a hook, both are only used on
+/// the first render; later changes to the props do not affect the model. Providers of different models can be
+/// nested freely, and each of them is found by [`use_tea_consumer`] by its type.
+#[component]
+pub fn TeaProvider<T: TeaModel + TeaModelInit>(
+    #[props(default)] initial: Option<T>,
+    on_init: Option<Callback<TeaModelSignal<T>>>,
+    children: Element,
+) -> Element {
+    let model = use_tea_model_with(move || initial.unwrap_or_else(T::init));
+    use_context_provider(|| model);
+    use_hook(move || {
+        if let Some(on_init) = on_init {
+            on_init.call(model);
+        }
+    });
+
+    rsx! {
+        {children}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cmd;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static MODELS: RefCell<Vec<TeaModelSignal<Pot>>> = const { RefCell::new(Vec::new()) };
+        static STATES: RefCell<Option<(Pot, Kettle)>> = const { RefCell::new(None) };
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Pot {
+        cups: u8,
+    }
+
+    impl TeaModel for Pot {
+        type Action = u8;
+
+        fn update(&mut self, cups: Self::Action) -> Cmd<Self::Action> {
+            self.cups = cups;
+            Cmd::none()
+        }
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Kettle {
+        temperature: u8,
+    }
+
+    impl TeaModel for Kettle {
+        type Action = u8;
+
+        fn update(&mut self, temperature: Self::Action) -> Cmd<Self::Action> {
+            self.temperature = temperature;
+            Cmd::none()
+        }
+    }
+
+    #[component]
+    fn Guest() -> Element {
+        let pot = use_tea_consumer::<Pot>();
+        use_hook(|| MODELS.with_borrow_mut(|models| models.push(pot)));
+        rsx! {}
+    }
+
+    #[test]
+    fn consumers_get_the_live_model() {
+        fn app() -> Element {
+            let pot = use_tea_context::<Pot>();
+            use_hook(|| MODELS.with_borrow_mut(|models| models.push(pot)));
+            rsx! {
+                Guest {}
+            }
+        }
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+
+        let models = MODELS.take();
+        assert!(models[0] == models[1]);
+        dom.in_runtime(|| {
+            models[0].send_sync(4);
+            assert_eq!(models[1].peek().cups, 4);
+        });
+    }
+
+    #[test]
+    fn nested_providers_of_different_models_coexist() {
+        #[component]
+        fn Table() -> Element {
+            let (pot, kettle) = (use_tea_consumer::<Pot>(), use_tea_consumer::<Kettle>());
+            STATES.set(Some((pot.peek().clone(), kettle.peek().clone())));
+            rsx! {}
+        }
+
+        fn app() -> Element {
+            rsx! {
+                TeaProvider::<Pot> { initial: Pot { cups: 2 },
+                    TeaProvider::<Kettle> { on_init: |kettle: TeaModelSignal<Kettle>| kettle.send_sync(80),
+                        Table {}
+                    }
+                }
+            }
+        }
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+
+        let (pot, kettle) = STATES.take().expect("the table is rendered");
+        assert_eq!(pot.cups, 2);
+        assert_eq!(kettle.temperature, 80, "on_init runs before the children render");
+    }
+}
@@ -0,0 +1,124 @@
+//! Debounced dispatching of actions.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::{dioxus_core::Task, prelude::WritableExt};
+use std::{collections::HashMap, hash::Hash, time::Duration};
+
+/// A model whose actions can be sent with [`TeaModelSignal::send_debounced`].
+pub trait TeaDebounce: TeaModel {
+    /// Identifies which actions are debounced together.
+    type Key: Clone + Eq + Hash + 'static;
+
+    /// Returns the debounce key of an action.
+    ///
+    /// Rapid actions with the same key coalesce into the last one, while actions with different keys are debounced
+    /// independently. For an enum of actions, `std::mem::discriminant(action)` debounces each variant separately.
+    fn debounce_key(action: &Self::Action) -> Self::Key;
+}
+
+impl<T: TeaDebounce> TeaModelSignal<T> {
+    /// Sends the action once `delay` has passed without another debounced action with the same key.
+    ///
+    /// Every call cancels the pending action with the same [`debounce_key`](TeaDebounce::debounce_key) and restarts
+    /// the timer, so of a burst of actions only the last one is delivered. The timer is tied to the component that
+    /// owns the model.
+    pub fn send_debounced(&self, action: T::Action, delay: Duration) {
+        let key = T::debounce_key(&action);
+        let model = *self;
+        let clock = self.clock();
+
+        let timer = self.spawn(async move {
+            clock.sleep(delay).await;
+            model.with_timers(|timers| timers.remove(&T::debounce_key(&action)));
+            model.send(action);
+        });
+
+        if let Some(pending) = self.with_timers(|timers| timers.insert(key, timer)) {
+            pending.cancel();
+        }
+    }
+
+    /// Runs `f` with the pending debounce timers of this model.
+    fn with_timers<R>(&self, f: impl FnOnce(&mut HashMap<T::Key, Task>) -> R) -> R {
+        let mut debounced = self.debounced;
+        let mut debounced = debounced.write();
+        let timers = debounced
+            .get_or_insert_with(|| Box::new(HashMap::<T::Key, Task>::new()))
+            .downcast_mut()
+            .expect("debounce timers are always keyed by the model's debounce key");
+        f(timers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+    use std::mem::{discriminant, Discriminant};
+
+    #[derive(Default, Clone, PartialEq)]
+    struct Kettle {
+        temperature: u8,
+        volume: u8,
+        updates: usize,
+    }
+
+    enum KettleAction {
+        SetTemperature(u8),
+        SetVolume(u8),
+    }
+
+    impl TeaModel for Kettle {
+        type Action = KettleAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                KettleAction::SetTemperature(temperature) => self.temperature = temperature,
+                KettleAction::SetVolume(volume) => self.volume = volume,
+            }
+            self.updates += 1;
+            Cmd::none()
+        }
+    }
+
+    impl TeaDebounce for Kettle {
+        type Key = Discriminant<KettleAction>;
+
+        fn debounce_key(action: &Self::Action) -> Self::Key {
+            discriminant(action)
+        }
+    }
+
+    #[tokio::test]
+    async fn rapid_actions_resolve_to_the_last_one() {
+        let (mut dom, kettle) = mount_model(use_tea_model::<Kettle>);
+
+        dom.in_runtime(|| {
+            for temperature in [70, 75, 80] {
+                kettle.send_debounced(KettleAction::SetTemperature(temperature), Duration::from_millis(10));
+            }
+        });
+        run(&mut dom).await;
+
+        let kettle = dom.in_runtime(|| kettle.peek().clone());
+        assert_eq!(kettle.temperature, 80);
+        assert_eq!(kettle.updates, 1, "Only the last action is delivered");
+    }
+
+    #[tokio::test]
+    async fn distinct_keys_debounce_independently() {
+        let (mut dom, kettle) = mount_model(use_tea_model::<Kettle>);
+
+        dom.in_runtime(|| {
+            kettle.send_debounced(KettleAction::SetTemperature(80), Duration::from_millis(10));
+            kettle.send_debounced(KettleAction::SetVolume(2), Duration::from_millis(10));
+        });
+        run(&mut dom).await;
+
+        let kettle = dom.in_runtime(|| kettle.peek().clone());
+        assert_eq!((kettle.temperature, kettle.volume), (80, 2));
+    }
+}
@@ -0,0 +1,162 @@
+//! A time-travel panel for inspecting and rewinding TEA models during development.
+
+use crate::{
+    history::{use_tea_model_with_labeled_history, Snapshot},
+    source, TeaHistorySignal, TeaModel, TeaModelInit, UNKNOWN_SOURCE,
+};
+use dioxus::prelude::*;
+use std::fmt::Debug;
+
+#[must_use]
+/// Creates a new `TeaHistorySignal` like [`use_tea_model_with_history`](crate::use_tea_model_with_history), which
+/// also remembers the action behind every snapshot, so [`TeaDevtools`] can list them. Actions sent with
+/// [`send_from`](crate::TeaModelSignal::send_from) are listed with their source.
+pub fn use_tea_model_with_devtools<T>(max_history: usize) -> TeaHistorySignal<T>
+where
+    T: TeaModel + TeaModelInit,
+    T::Action: Debug,
+{
+    use_tea_model_with_labeled_history(max_history, |action| match source::current() {
+        UNKNOWN_SOURCE => Some(format!("{action:?}")),
+        source => Some(format!("{action:?} from {source}")),
+    })
+}
+
+/// An entry of the devtools panel: a recorded state and the action that produced it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Entry {
+    action: Option<String>,
+    state: String,
+    current: bool,
+}
+
+impl<T: TeaModel + Debug> TeaHistorySignal<T> {
+    /// Lists every recorded state, oldest first, including the current one and the ones that can be redone.
+    pub(crate) fn entries(&self) -> Vec<Entry> {
+        let history = self.history.read();
+        let entry = |snapshot: &Snapshot<T>| Entry {
+            action: snapshot.action.clone(),
+            state: format!("{:?}", snapshot.state),
+            current: false,
+        };
+
+        let current = Entry {
+            action: history.action.clone(),
+            state: format!("{:?}", *self.peek()),
+            current: true,
+        };
+        history
+            .past
+            .iter()
+            .map(entry)
+            .chain(std::iter::once(current))
+            .chain(history.future.iter().rev().map(entry))
+            .collect()
+    }
+
+    /// Undoes or redoes changes until the entry at `index` of [`entries`](Self::entries) is the current state.
+    pub(crate) fn jump_to(&self, index: usize) {
+        let current = self.history.peek().past.len();
+        for _ in index..current {
+            self.undo();
+        }
+        for _ in current..index {
+            self.redo();
+        }
+    }
+}
+
+/// Lists the actions recorded by a model from [`use_tea_model_with_devtools`], together with the state each of them
+/// produced. Clicking an entry restores its state, and the entries after it can be redone again by clicking them.
+///
+/// The panel only renders in debug builds; in release builds it renders nothing.
+#[component]
+pub fn TeaDevtools<T: TeaModel + Debug>(model: TeaHistorySignal<T>) -> Element
+where
+    T::Action: Debug,
+{
+    if !cfg!(debug_assertions) {
+        return rsx! {};
+    }
+
+    rsx! {
+        ol { class: "tea-devtools",
+            for (index , entry) in model.entries().into_iter().enumerate() {
+                li {
+                    key: "{index}",
+                    class: if entry.current { "tea-devtools-current" },
+                    onclick: move |_| model.jump_to(index),
+                    code { {entry.action.unwrap_or_else(|| "(initial)".to_string())} }
+                    " → "
+                    code { "{entry.state}" }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::mount, Cmd};
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Cup {
+        sugar: u8,
+    }
+
+    impl TeaModel for Cup {
+        type Action = u8;
+
+        fn update(&mut self, sugar: Self::Action) -> Cmd<Self::Action> {
+            self.sugar += sugar;
+            Cmd::none()
+        }
+    }
+
+    fn app() -> Element {
+        let model = use_tea_model_with_devtools::<Cup>(10);
+        use_context_provider(|| model);
+        rsx! {
+            TeaDevtools { model }
+        }
+    }
+
+    fn labels(entries: &[Entry]) -> Vec<Option<&str>> {
+        entries.iter().map(|entry| entry.action.as_deref()).collect()
+    }
+
+    #[test]
+    fn entries_list_the_actions_and_the_states_they_produced() {
+        let (dom, cup) = mount::<TeaHistorySignal<Cup>>(app);
+        let entries = dom.in_runtime(|| {
+            cup.send_sync(1);
+            cup.send_sync(2);
+            cup.entries()
+        });
+
+        assert_eq!(labels(&entries), [None, Some("1"), Some("2")]);
+        assert_eq!(entries[2].state, "Cup { sugar: 3 }");
+        assert!(entries[2].current);
+    }
+
+    #[test]
+    fn jumping_restores_the_snapshot_and_keeps_the_rest_redoable() {
+        let (dom, cup) = mount::<TeaHistorySignal<Cup>>(app);
+        dom.in_runtime(|| {
+            for sugar in [1, 2, 3] {
+                cup.send_sync(sugar);
+            }
+
+            cup.jump_to(1);
+            assert_eq!(cup.peek().sugar, 1);
+            let entries = cup.entries();
+            assert_eq!(labels(&entries), [None, Some("1"), Some("2"), Some("3")]);
+            assert!(entries[1].current);
+
+            cup.jump_to(3);
+            assert_eq!(cup.peek().sugar, 6);
+            assert!(!cup.can_redo());
+        });
+    }
+}
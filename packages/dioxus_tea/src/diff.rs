@@ -0,0 +1,151 @@
+//! Deltas between the states of TEA models, enabled with the `diff` feature.
+
+use crate::{TeaModel, TeaModelSignal};
+use futures_util::{Stream, StreamExt};
+
+/// A state that can describe the difference to another state as a delta, which is cheaper to send than the state.
+///
+/// The [`TeaDiff` derive](derive@crate::TeaDiff) of the `derive` feature generates field-level deltas for structs.
+/// Associated types cannot have a default yet, so a state that is not worth diffing sets `type Delta = Self` and
+/// returns a clone of the new state, which is what the derive does for enums:
+///
+/// ```rust,ignore
+/// impl TeaDiff for Status {
+///     type Delta = Self;
+///
+///     fn diff(_old: &Self, new: &Self) -> Self::Delta {
+///         new.clone()
+///     }
+///
+///     fn apply_delta(&mut self, delta: Self::Delta) {
+///         *self = delta;
+///     }
+/// }
+/// ```
+///
+/// To transmit deltas, e.g. to devtools in another process, make the delta serializable; the derive passes the
+/// traits listed in `#[tea_diff(derive(...))]` on to the delta, and with `Option` fields, serde skips nothing on its
+/// own, so a delta of a struct serializes every unchanged field as `null`. The receiver must hold the state the
+/// delta was taken from: deltas only apply on top of the state before them, in order.
+pub trait TeaDiff {
+    /// The difference between two states.
+    type Delta;
+
+    /// Returns the delta that turns `old` into `new`.
+    fn diff(old: &Self, new: &Self) -> Self::Delta;
+
+    /// Applies a delta returned by [`diff`](Self::diff), turning the old state into the new one.
+    fn apply_delta(&mut self, delta: Self::Delta);
+}
+
+impl<T: TeaModel + TeaDiff> TeaModelSignal<T> {
+    /// Returns a stream that yields the delta to the previous state every time the state changes.
+    ///
+    /// This is [`changes`](Self::changes), diffed: the first delta is relative to the state at the time of this
+    /// call, and every following one to the state before it, so applying all of them in order to a clone of the
+    /// current state reproduces the state of the model.
+    pub fn deltas(&self) -> impl Stream<Item = T::Delta> {
+        let mut previous = self.peek().clone();
+        self.changes().map(move |state| {
+            let delta = T::diff(&previous, &state);
+            previous = state;
+            delta
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Pot {
+        cups: u8,
+        sugar: u8,
+    }
+
+    impl TeaModel for Pot {
+        type Action = u8;
+
+        fn update(&mut self, cups: Self::Action) -> Cmd<Self::Action> {
+            self.cups = cups;
+            Cmd::none()
+        }
+    }
+
+    impl TeaDiff for Pot {
+        type Delta = (Option<u8>, Option<u8>);
+
+        fn diff(old: &Self, new: &Self) -> Self::Delta {
+            (
+                (old.cups != new.cups).then_some(new.cups),
+                (old.sugar != new.sugar).then_some(new.sugar),
+            )
+        }
+
+        fn apply_delta(&mut self, (cups, sugar): Self::Delta) {
+            self.cups = cups.unwrap_or(self.cups);
+            self.sugar = sugar.unwrap_or(self.sugar);
+        }
+    }
+
+    #[tokio::test]
+    async fn deltas_reproduce_the_state() {
+        let (mut dom, pot) = mount_model(use_tea_model::<Pot>);
+        let (mut deltas, mut replica) = dom.in_runtime(|| (pot.deltas(), pot.peek().clone()));
+        dom.in_runtime(|| {
+            pot.send(2);
+            pot.send(3);
+        });
+        run(&mut dom).await;
+
+        for expected in [(Some(2), None), (Some(3), None)] {
+            let delta = deltas.next().await.expect("the model is still alive");
+            assert_eq!(delta, expected);
+            replica.apply_delta(delta);
+        }
+        assert_eq!(replica, dom.in_runtime(|| pot.peek().clone()));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_deltas_replace_enums_and_diff_struct_fields() {
+        #[derive(Clone, PartialEq, Debug, crate::TeaDiff)]
+        enum Status {
+            EmptyCup,
+            Water(u8),
+        }
+
+        #[derive(Clone, PartialEq, Debug, crate::TeaDiff)]
+        #[tea_diff(derive(Debug))]
+        struct Cup {
+            status: Status,
+            sugar: u8,
+        }
+
+        assert_eq!(Status::diff(&Status::EmptyCup, &Status::Water(80)), Status::Water(80));
+
+        let mut cup = Cup {
+            status: Status::EmptyCup,
+            sugar: 1,
+        };
+        let poured = Cup {
+            status: Status::Water(80),
+            ..cup.clone()
+        };
+        let delta = Cup::diff(&cup, &poured);
+        assert_eq!(
+            delta,
+            CupDelta {
+                status: Some(Status::Water(80)),
+                sugar: None
+            }
+        );
+        cup.apply_delta(delta);
+        assert_eq!(cup, poured);
+    }
+}
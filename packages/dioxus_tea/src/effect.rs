@@ -0,0 +1,173 @@
+//! Running side effects when the state of a TEA model changes, or settles.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::prelude::{spawn, use_effect, use_hook, use_memo, use_signal, ReadableExt, WritableExt};
+use futures_channel::mpsc;
+use futures_util::{
+    future::{self, Either},
+    StreamExt,
+};
+use std::time::Duration;
+
+/// Runs `f` with the new state every time the state of `model` changes.
+///
+/// `f` runs once after the component has mounted, with the initial state, and after that only when the state
+/// changed by `PartialEq`; updates that leave the state equal, and re-renders of the component, do not trigger it.
+/// Like any effect, `f` runs after rendering, so it is free to [`send`](TeaModelSignal::send) follow-up actions.
+///
+/// Every change clones the state once, to compare it against the previous one.
+pub fn use_tea_effect<T: TeaModel>(model: TeaModelSignal<T>, mut f: impl FnMut(&T) + 'static) {
+    let state = use_memo(move || model.read().clone());
+    use_effect(move || f(&state.read()));
+}
+
+/// Runs `f` with the previous and the new state every time the state of `model` changes.
+///
+/// This is meant for edges, like "went from `Water` to `TeaReady`". Unlike [`use_tea_effect`], `f` is skipped on
+/// mount: the first call happens on the first change, with the state the component mounted with as the previous
+/// state. Changes are detected by [`TeaModel::changed`].
+pub fn use_tea_transition<T: TeaModel>(model: TeaModelSignal<T>, mut f: impl FnMut(&T, &T) + 'static) {
+    let mut previous = use_signal(|| model.peek().clone());
+    use_tea_effect(model, move |state| {
+        let previous = std::mem::replace(&mut *previous.write(), state.clone());
+        if T::changed(&previous, state) {
+            f(&previous, state);
+        }
+    });
+}
+
+/// Runs `f` with the state of `model` once no action has arrived for `idle_for`, and the queue is empty.
+///
+/// This is meant for work that should wait until the user stopped interacting, like an autosave: every action
+/// restarts the timer, including the actions produced by commands, so `f` only runs once everything has drained and
+/// stayed quiet. It runs once per quiet period, not on mount; the next call waits for the next action. Unlike
+/// debouncing every action with [`send_debounced`](TeaModelSignal::send_debounced), this covers all actions at once.
+///
+/// The timer stops when the component that calls this hook unmounts. `idle_for` is only used on the first render.
+pub fn use_tea_idle<T: TeaModel>(model: TeaModelSignal<T>, idle_for: Duration, mut f: impl FnMut(&T) + 'static) {
+    use_hook(|| {
+        let (arrived_tx, mut arrived) = mpsc::unbounded::<()>();
+        let mut observers = model.observers;
+        observers.write().push(Box::new(move |_action: &T::Action, _source| {
+            arrived_tx.unbounded_send(()).is_ok()
+        }));
+
+        spawn(async move {
+            while arrived.next().await.is_some() {
+                loop {
+                    match future::select(arrived.next(), std::pin::pin!(model.clock().sleep(idle_for))).await {
+                        Either::Left((None, _)) => return,
+                        Either::Right(_) if model.is_idle() => break,
+                        // another action arrived, or the queue has not drained yet, so the timer starts over
+                        _ => {}
+                    }
+                }
+                f(&model.peek());
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount, run, run_for},
+        use_tea_model, Cmd,
+    };
+    use dioxus::prelude::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static SEEN: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+        static TRANSITIONS: RefCell<Vec<(u8, u8)>> = const { RefCell::new(Vec::new()) };
+        static SAVED: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Teapot {
+        cups: u8,
+    }
+
+    impl TeaModel for Teapot {
+        type Action = u8;
+
+        fn update(&mut self, cups: Self::Action) -> Cmd<Self::Action> {
+            self.cups = cups;
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn effects_only_run_when_the_state_changed() {
+        fn app() -> Element {
+            let model = use_tea_model::<Teapot>();
+            use_context_provider(|| model);
+            use_tea_effect(model, move |teapot| {
+                SEEN.with_borrow_mut(|seen| seen.push(teapot.cups));
+                if teapot.cups == 1 {
+                    // effects may dispatch follow-up actions
+                    model.send(2);
+                }
+            });
+            rsx! {}
+        }
+
+        let (mut dom, teapot) = mount::<TeaModelSignal<Teapot>>(app);
+        run(&mut dom).await;
+        dom.in_runtime(|| teapot.send(1));
+        run(&mut dom).await;
+        dom.in_runtime(|| teapot.send(2));
+        run(&mut dom).await;
+
+        assert_eq!(
+            SEEN.take(),
+            [0, 1, 2],
+            "Setting the same number of cups is not a change"
+        );
+    }
+
+    #[tokio::test]
+    async fn transitions_report_the_previous_state() {
+        fn app() -> Element {
+            let model = use_tea_model::<Teapot>();
+            use_context_provider(|| model);
+            use_tea_transition(model, |previous, teapot| {
+                TRANSITIONS.with_borrow_mut(|transitions| transitions.push((previous.cups, teapot.cups)));
+            });
+            rsx! {}
+        }
+
+        let (mut dom, teapot) = mount::<TeaModelSignal<Teapot>>(app);
+        run(&mut dom).await;
+        for cups in [1, 1, 3] {
+            dom.in_runtime(|| teapot.send(cups));
+            run(&mut dom).await;
+        }
+
+        assert_eq!(TRANSITIONS.take(), [(0, 1), (1, 3)], "Mounting is not a transition");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_callbacks_wait_for_sustained_idleness() {
+        fn app() -> Element {
+            let model = use_tea_model::<Teapot>();
+            use_context_provider(|| model);
+            use_tea_idle(model, Duration::from_millis(30), |teapot| {
+                SAVED.with_borrow_mut(|saved| saved.push(teapot.cups));
+            });
+            rsx! {}
+        }
+
+        let (mut dom, teapot) = mount::<TeaModelSignal<Teapot>>(app);
+        run_for(&mut dom, Duration::from_millis(50)).await;
+        for cups in [1, 2] {
+            dom.in_runtime(|| teapot.send(cups));
+            run_for(&mut dom, Duration::from_millis(20)).await;
+        }
+        assert!(SAVED.with_borrow(Vec::is_empty), "Every action restarts the timer");
+
+        run_for(&mut dom, Duration::from_millis(100)).await;
+        assert_eq!(SAVED.take(), [2], "The callback runs once per quiet period");
+    }
+}
@@ -0,0 +1,138 @@
+//! A component that swaps its content for a fallback while a fallible model is in an error.
+
+use crate::{TeaModelSignal, TryTeaModel};
+use dioxus::prelude::*;
+use dioxus_sdk::time::sleep;
+use std::{rc::Rc, time::Duration};
+
+/// Renders its children, or the output of `render_error` while the most recent action of `model` failed.
+///
+/// `render_error` is a [`Callback`], so it can be a plain function like `fn(Rc<T::Error>) -> Element` as well as a
+/// closure.
+///
+/// The children are rendered again as soon as an action is applied successfully. With `dismiss_after`, the error is
+/// also dismissed once it has been displayed for that long; the error itself stays in the model's error sink.
+#[component]
+pub fn TeaErrorBoundary<T: TryTeaModel>(
+    model: TeaModelSignal<T>,
+    render_error: Callback<Rc<T::Error>, Element>,
+    #[props(default)] dismiss_after: Option<Duration>,
+    children: Element,
+) -> Element {
+    let mut dismissed = use_signal(|| None::<Rc<T::Error>>);
+
+    use_effect(move || {
+        if let (Some(delay), Some(error)) = (dismiss_after, model.errors()) {
+            spawn(async move {
+                sleep(delay).await;
+                dismissed.set(Some(error));
+            });
+        }
+    });
+
+    let error = model.errors().filter(|error| {
+        !dismissed
+            .read()
+            .as_ref()
+            .is_some_and(|dismissed| Rc::ptr_eq(dismissed, error))
+    });
+    match error {
+        Some(error) => render_error.call(error),
+        None => children,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount, run},
+        use_tea_model, Cmd,
+    };
+    use std::cell::Cell;
+
+    thread_local! {
+        static SHOWING: Cell<&'static str> = const { Cell::new("") };
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Kettle {
+        temperature: u8,
+    }
+
+    #[derive(Debug)]
+    struct TooHot;
+
+    impl TryTeaModel for Kettle {
+        type Action = u8;
+        type Error = TooHot;
+
+        fn try_update(&mut self, temperature: Self::Action) -> Result<Cmd<Self::Action>, Self::Error> {
+            if temperature > 100 {
+                return Err(TooHot);
+            }
+            self.temperature = temperature;
+            Ok(Cmd::none())
+        }
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn too_hot(_error: Rc<TooHot>) -> Element {
+        SHOWING.set("error");
+        rsx! { "Too hot!" }
+    }
+
+    #[component]
+    fn Temperature() -> Element {
+        SHOWING.set("temperature");
+        rsx! {}
+    }
+
+    #[tokio::test]
+    async fn errors_are_displayed_until_an_action_succeeds() {
+        fn app() -> Element {
+            let model = use_tea_model::<Kettle>();
+            use_context_provider(|| model);
+            rsx! {
+                TeaErrorBoundary { model, render_error: too_hot, Temperature {} }
+            }
+        }
+
+        let (mut dom, kettle) = mount::<TeaModelSignal<Kettle>>(app);
+        assert_eq!(SHOWING.get(), "temperature");
+
+        dom.in_runtime(|| kettle.send(120));
+        run(&mut dom).await;
+        assert_eq!(SHOWING.get(), "error");
+
+        dom.in_runtime(|| kettle.send(80));
+        run(&mut dom).await;
+        assert_eq!(SHOWING.get(), "temperature");
+    }
+
+    #[tokio::test]
+    async fn errors_can_be_dismissed_automatically() {
+        fn app() -> Element {
+            let model = use_tea_model::<Kettle>();
+            use_context_provider(|| model);
+            rsx! {
+                TeaErrorBoundary {
+                    model,
+                    render_error: too_hot,
+                    dismiss_after: Duration::from_millis(10),
+                    Temperature {}
+                }
+            }
+        }
+
+        let (mut dom, kettle) = mount::<TeaModelSignal<Kettle>>(app);
+        dom.in_runtime(|| kettle.send(120));
+        run(&mut dom).await;
+
+        assert_eq!(SHOWING.get(), "temperature");
+        assert!(
+            dom.in_runtime(|| kettle.errors().is_some()),
+            "The error is still in the sink"
+        );
+    }
+}
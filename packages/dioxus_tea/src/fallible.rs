@@ -0,0 +1,115 @@
+//! Fallible models, whose updates may fail with an error instead of encoding it in the state.
+
+use crate::{changed, Changed, Cmd, TeaModel, TeaModelSignal, TeaPanicPolicy};
+use dioxus::prelude::ReadableExt;
+use std::{any::Any, collections::VecDeque, rc::Rc};
+
+/// Variant of [`TeaModel`] whose updates can fail.
+///
+/// Every `TryTeaModel` is a `TeaModel` through a blanket implementation, so it can be used with
+/// [`use_tea_model`](crate::use_tea_model) and all other hooks of this crate. When an update fails, the coroutine
+/// routes the error to the model's error sink, which can be read with [`TeaModelSignal::errors`]. Infallible models
+/// simply keep implementing `TeaModel`.
+///
+/// Use this for truly exceptional failures, and keep modelling expected outcomes (like "the cup has no tea bag")
+/// in the state itself.
+pub trait TryTeaModel: 'static + Clone + PartialEq {
+    /// The type of actions that can be processed by this model.
+    type Action;
+
+    /// The type of error an update can fail with.
+    type Error: 'static;
+
+    /// Updates the model state based on the provided action, or fails with an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the action could not be applied. Any changes made to the state before failing are
+    /// kept.
+    fn try_update(&mut self, action: Self::Action) -> Result<Cmd<Self::Action>, Self::Error>;
+
+    /// Updates the model like [`try_update`](Self::try_update), and reports whether a successful update changed the
+    /// state; see [`TeaModel::update_checked`]. A failed update always counts as changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the action could not be applied, like `try_update`.
+    fn try_update_checked(&mut self, action: Self::Action) -> Result<(Cmd<Self::Action>, Changed), Self::Error> {
+        self.try_update(action).map(|cmd| (cmd, Changed::Yes))
+    }
+
+    /// Returns `true` if `action` cancels the commands that are still running; see [`TeaModel::cancels_effects`].
+    fn cancels_effects(_action: &Self::Action) -> bool {
+        false
+    }
+
+    /// Reports an action that a guard of the model rejected; see [`TeaModel::on_rejected`].
+    fn on_rejected(&self, _action: &Self::Action) {}
+
+    /// Decides how the model recovers after `try_update` panicked; see [`TeaModel::on_panic`].
+    fn on_panic(&self, _message: &str) -> TeaPanicPolicy<Self> {
+        TeaPanicPolicy::Skip
+    }
+
+    /// Drops or merges redundant actions before they are applied; see [`TeaModel::coalesce`].
+    fn coalesce(_pending: &mut VecDeque<Self::Action>) {}
+
+    /// Decides whether an update changed the state; see [`TeaModel::changed`].
+    fn changed(old: &Self, new: &Self) -> bool {
+        old != new
+    }
+}
+
+impl<T: TryTeaModel> TeaModel for T {
+    type Action = T::Action;
+
+    /// Updates the model, discarding the error when the update fails.
+    fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+        self.try_update(action).unwrap_or_default()
+    }
+
+    /// Updates the model like [`update`](TeaModel::update), counting a failed update as changed.
+    fn update_checked(&mut self, action: Self::Action) -> (Cmd<Self::Action>, Changed) {
+        self.try_update_checked(action)
+            .unwrap_or_else(|_| (Cmd::none(), Changed::Yes))
+    }
+
+    fn cancels_effects(action: &Self::Action) -> bool {
+        <T as TryTeaModel>::cancels_effects(action)
+    }
+
+    fn on_rejected(&self, action: &Self::Action) {
+        <T as TryTeaModel>::on_rejected(self, action);
+    }
+
+    fn on_panic(&self, message: &str) -> TeaPanicPolicy<Self> {
+        <T as TryTeaModel>::on_panic(self, message)
+    }
+
+    fn coalesce(pending: &mut VecDeque<Self::Action>) {
+        <T as TryTeaModel>::coalesce(pending);
+    }
+
+    fn changed(old: &Self, new: &Self) -> bool {
+        <T as TryTeaModel>::changed(old, new)
+    }
+
+    fn try_apply(&mut self, action: Self::Action) -> Result<Cmd<Self::Action>, Rc<dyn Any>> {
+        let (cmd, reported) = self
+            .try_update_checked(action)
+            .map_err(|error| Rc::new(error) as Rc<dyn Any>)?;
+        changed::report(reported);
+        Ok(cmd)
+    }
+}
+
+impl<T: TryTeaModel> TeaModelSignal<T> {
+    #[must_use]
+    /// Returns the error of the most recently processed action, if it failed.
+    ///
+    /// This subscribes the current component to the error sink. The error is cleared as soon as a subsequent action
+    /// is applied successfully.
+    pub fn errors(&self) -> Option<Rc<T::Error>> {
+        self.error.read().clone().and_then(|error| error.downcast().ok())
+    }
+}
@@ -0,0 +1,104 @@
+//! App-wide TEA models that are not tied to a component.
+
+use crate::{apply, new_tea_model, Queue, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::{
+    dioxus_core::{Runtime, ScopeId},
+    prelude::use_hook,
+};
+
+#[must_use]
+/// Returns the app-wide `TeaModelSignal` for the given `TeaModel`, creating it on the first call.
+///
+/// Every component that calls this hook gets the same handle, without passing it through context. The model is
+/// created lazily, the first time any component asks for it, and is owned by the root scope rather than by that
+/// component: its state and coroutine live as long as the app, and commands keep running when the component that
+/// created it unmounts. A model from [`use_tea_model`](crate::use_tea_model), on the other hand, is dropped together
+/// with its component, and each call creates a new, independent instance.
+///
+/// The model is stored in the root context, so it can also be fetched with `use_context`.
+pub fn use_global_tea_model<T: TeaModel + TeaModelInit>() -> TeaModelSignal<T> {
+    use_hook(global_tea_model)
+}
+
+/// Returns the app-wide model of type `T` from the root context, creating it on the first call.
+///
+/// This is the one slot behind both [`use_global_tea_model`] and [`TeaRegistry::get`](crate::TeaRegistry::get), so
+/// both hand out the same model.
+pub(crate) fn global_tea_model<T: TeaModel + TeaModelInit>() -> TeaModelSignal<T> {
+    let runtime = Runtime::current();
+    runtime.has_context(ScopeId::ROOT).unwrap_or_else(|| {
+        let model = new_tea_model(ScopeId::ROOT, T::init(), Box::new(apply), Queue::Unbounded);
+        runtime.provide_context(ScopeId::ROOT, model)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount, run},
+        Cmd,
+    };
+    use dioxus::prelude::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static MODELS: RefCell<Vec<TeaModelSignal<Visits>>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Visits {
+        count: u32,
+    }
+
+    impl TeaModel for Visits {
+        type Action = ();
+
+        fn update(&mut self, (): Self::Action) -> Cmd<Self::Action> {
+            self.count += 1;
+            Cmd::none()
+        }
+    }
+
+    #[component]
+    fn Visitor() -> Element {
+        let model = use_global_tea_model::<Visits>();
+        use_hook(|| {
+            model.send(());
+            MODELS.with_borrow_mut(|models| models.push(model));
+        });
+        rsx! {}
+    }
+
+    #[tokio::test]
+    async fn the_model_is_shared_and_outlives_its_creator() {
+        fn app() -> Element {
+            let show = use_signal(|| true);
+            use_context_provider(|| show);
+            rsx! {
+                if show() {
+                    Visitor {}
+                }
+                Visitor {}
+            }
+        }
+
+        let (mut dom, mut show) = mount::<Signal<bool>>(app);
+        run(&mut dom).await;
+
+        let models = MODELS.take();
+        assert_eq!(models.len(), 2);
+        assert!(models[0] == models[1], "Both components get the same model");
+        assert_eq!(dom.in_runtime(|| models[0].peek().count), 2);
+
+        dom.in_runtime(|| show.set(false));
+        run(&mut dom).await;
+        dom.in_runtime(|| models[0].send(()));
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| models[0].peek().count),
+            3,
+            "The model keeps working after the component that created it unmounted"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Undo/redo support for TEA models.
+
+use crate::{apply, use_tea_model_processed, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::prelude::{use_signal, ReadableExt, Signal, WritableExt};
+use std::{collections::VecDeque, ops::Deref};
+
+/// A [`TeaModelSignal`] that keeps a history of previous states, so changes can be undone and redone.
+///
+/// It dereferences to the underlying `TeaModelSignal`, so actions are sent and state is read just like with a plain
+/// model.
+#[derive(Clone, PartialEq)]
+pub struct TeaHistorySignal<T: TeaModel> {
+    model: TeaModelSignal<T>,
+    pub(crate) history: Signal<History<T>>,
+}
+
+impl<T: TeaModel> Copy for TeaHistorySignal<T> {}
+
+impl<T: TeaModel> TeaHistorySignal<T> {
+    #[must_use]
+    /// Returns the underlying model signal, e.g. to pass it to components that do not need the history.
+    pub fn model(&self) -> TeaModelSignal<T> {
+        self.model
+    }
+
+    #[must_use]
+    /// Returns `true` if there is a previous state to return to.
+    pub fn can_undo(&self) -> bool {
+        !self.history.read().past.is_empty()
+    }
+
+    #[must_use]
+    /// Returns `true` if there is an undone state to restore.
+    pub fn can_redo(&self) -> bool {
+        !self.history.read().future.is_empty()
+    }
+
+    /// Restores the state from before the most recent change.
+    ///
+    /// The restored state is written to the model directly, without running `update` again, but like any other
+    /// change it bumps the [`version`](TeaModelSignal::version) and reaches [`changes`](TeaModelSignal::changes).
+    /// Does nothing if there is no history.
+    pub fn undo(&self) {
+        let mut history = self.history;
+        let Some(previous) = history.write().past.pop_back() else {
+            return;
+        };
+
+        let current = self.model.inner.peek().clone();
+        self.model.commit(previous.state);
+        let mut history = history.write();
+        let action = std::mem::replace(&mut history.action, previous.action);
+        history.future.push(Snapshot { state: current, action });
+    }
+
+    /// Restores the state that was most recently undone.
+    ///
+    /// Like [`undo`](Self::undo), this does not run `update`. Does nothing if there is nothing to redo.
+    pub fn redo(&self) {
+        let mut history = self.history;
+        let Some(next) = history.write().future.pop() else {
+            return;
+        };
+
+        let current = self.model.inner.peek().clone();
+        self.model.commit(next.state);
+        let mut history = history.write();
+        let action = std::mem::replace(&mut history.action, next.action);
+        history.push_past(Snapshot { state: current, action });
+    }
+}
+
+impl<T: TeaModel> Deref for TeaHistorySignal<T> {
+    type Target = TeaModelSignal<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.model
+    }
+}
+
+#[must_use]
+/// Creates a new `TeaHistorySignal` for the given `TeaModel`, keeping at most `max_history` previous states.
+///
+/// A snapshot of the state is taken before every update that changes the state; when the history is full, the
+/// oldest snapshot is dropped. Applying a new change clears the states that can be redone.
+pub fn use_tea_model_with_history<T: TeaModel + TeaModelInit>(max_history: usize) -> TeaHistorySignal<T> {
+    use_tea_model_with_labeled_history(max_history, |_| None)
+}
+
+/// Like [`use_tea_model_with_history`], labelling every snapshot with the action that produced it.
+pub(crate) fn use_tea_model_with_labeled_history<T: TeaModel + TeaModelInit>(
+    max_history: usize,
+    label: fn(&T::Action) -> Option<String>,
+) -> TeaHistorySignal<T> {
+    let mut history = use_signal(|| History::new(max_history));
+
+    let model = use_tea_model_processed(T::init, move || {
+        Box::new(move |state: &mut T, error, action| {
+            let previous = state.clone();
+            let action_label = label(&action);
+            let cmd = apply(state, error, action);
+            if T::changed(&previous, state) {
+                history.write().record(previous, action_label);
+            }
+            cmd
+        })
+    });
+
+    TeaHistorySignal { model, history }
+}
+
+/// Previous and undone states of a model.
+pub(crate) struct History<T> {
+    pub(crate) past: VecDeque<Snapshot<T>>,
+    pub(crate) future: Vec<Snapshot<T>>,
+    /// Label of the action that produced the current state.
+    pub(crate) action: Option<String>,
+    capacity: usize,
+}
+
+/// A state in the history, together with the label of the action that produced it.
+pub(crate) struct Snapshot<T> {
+    pub(crate) state: T,
+    pub(crate) action: Option<String>,
+}
+
+impl<T> History<T> {
+    fn new(max_history: usize) -> Self {
+        Self {
+            past: VecDeque::with_capacity(max_history),
+            future: Vec::new(),
+            action: None,
+            capacity: max_history,
+        }
+    }
+
+    /// Records the state from before a new change, invalidating everything that could be redone.
+    fn record(&mut self, state: T, action: Option<String>) {
+        self.future.clear();
+        let previous_action = std::mem::replace(&mut self.action, action);
+        self.push_past(Snapshot {
+            state,
+            action: previous_action,
+        });
+    }
+
+    fn push_past(&mut self, state: Snapshot<T>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.past.len() == self.capacity {
+            self.past.pop_front();
+        }
+        self.past.push_back(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::mount_model, Cmd};
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Mug {
+        sips: u8,
+    }
+
+    impl TeaModel for Mug {
+        type Action = ();
+
+        fn update(&mut self, (): Self::Action) -> Cmd<Self::Action> {
+            self.sips += 1;
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn undone_states_are_written_like_any_other_change() {
+        let (dom, mug) = mount_model(|| use_tea_model_with_history::<Mug>(2));
+        let undone = dom.in_runtime(|| {
+            mug.send_sync(());
+            let version = mug.version();
+            let undone = mug.next_change();
+            mug.undo();
+            assert_eq!(mug.version(), version + 1);
+            assert!(mug.last_changed());
+            undone
+        });
+        assert_eq!(undone.await, Mug { sips: 0 });
+        dom.in_runtime(|| {
+            mug.redo();
+            assert_eq!(mug.peek().sips, 1);
+        });
+    }
+
+    #[test]
+    fn oldest_snapshots_are_dropped_when_the_history_is_full() {
+        let mut history = History::new(2);
+        history.record(1, None);
+        history.record(2, None);
+        history.record(3, None);
+        let past: Vec<_> = history.past.iter().map(|snapshot| snapshot.state).collect();
+        assert_eq!(past, [2, 3]);
+    }
+
+    #[test]
+    fn recording_a_change_clears_the_redo_states() {
+        let mut history = History::new(2);
+        history.record(1, None);
+        history.future.push(Snapshot { state: 2, action: None });
+        history.record(3, None);
+        assert!(history.future.is_empty());
+    }
+
+    #[test]
+    fn snapshots_are_labelled_with_the_action_that_produced_them() {
+        let mut history = History::new(2);
+        history.record(0, Some("first".to_string()));
+        history.record(1, Some("second".to_string()));
+        let labels: Vec<_> = history.past.iter().map(|snapshot| snapshot.action.as_deref()).collect();
+        assert_eq!(labels, [None, Some("first")]);
+        assert_eq!(history.action.as_deref(), Some("second"));
+    }
+}
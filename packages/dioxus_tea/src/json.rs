@@ -0,0 +1,46 @@
+//! JSON snapshots of models and actions, enabled with the `serde` feature.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::prelude::ReadableExt;
+use serde::{de::DeserializeOwned, Serialize};
+
+impl<T> TeaModelSignal<T>
+where
+    T: TeaModel + Serialize + DeserializeOwned,
+{
+    #[must_use]
+    /// Serializes the current state to JSON, without subscribing the current component to changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state cannot be represented as JSON, e.g. because it contains a map with non-string keys.
+    pub fn snapshot_json(&self) -> String {
+        serde_json::to_string(&*self.inner.peek()).expect("model state should serialize to JSON")
+    }
+
+    /// Replaces the current state with the one deserialized from `json`.
+    ///
+    /// Like a snapshot, the restored state is written directly and does not go through `update`, but like
+    /// [`reset_with`](Self::reset_with), the write bumps the [`version`](Self::version) and reaches
+    /// [`changes`](Self::changes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and leaves the state untouched, if `json` is not a valid representation of the state.
+    pub fn restore_json(&self, json: &str) -> Result<(), serde_json::Error> {
+        let state = serde_json::from_str(json)?;
+        self.with_mut(|current| *current = state);
+        Ok(())
+    }
+}
+
+/// Serializes actions to JSON, e.g. to log them. Implemented for every serializable type.
+pub trait TeaActionJson: Serialize {
+    /// Returns the JSON representation of the action, or a JSON-encoded error message if it cannot be serialized.
+    fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|error| serde_json::Value::String(format!("unserializable action: {error}")).to_string())
+    }
+}
+
+impl<A: Serialize + ?Sized> TeaActionJson for A {}
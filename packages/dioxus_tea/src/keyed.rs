@@ -0,0 +1,139 @@
+//! Keyed TEA models, for lists of independent widgets that share a parent.
+
+use crate::{apply, new_tea_model, Queue, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::{
+    dioxus_core::{current_scope_id, parent_scope, Runtime, ScopeId},
+    prelude::{try_consume_context, use_hook, CopyValue, ReadableExt, WritableExt},
+};
+use std::{collections::HashMap, hash::Hash};
+
+/// The keyed models of one type, shared through context by the subtree they were created in.
+#[derive(Clone)]
+struct TeaKeyed<K: 'static, T: TeaModel> {
+    /// The scope that owns the registry and its models.
+    scope: ScopeId,
+    models: CopyValue<HashMap<K, TeaModelSignal<T>>>,
+}
+
+#[must_use]
+/// Returns the `TeaModelSignal` for `key`, creating it on first use.
+///
+/// Every distinct key gets its own, isolated instance, while components that pass the same key share one. This
+/// allows rendering a list of independent widgets, like three tea cups side by side, without providing a model per
+/// widget by hand.
+///
+/// Keys are compared by `Eq`, never just by their hash, so distinct keys always get distinct instances. The instances
+/// are kept in a registry that is created by the first component that calls this hook with that type of key, in the
+/// context of its parent, so siblings and their descendants share it. The key is looked up on every render: when a
+/// component re-renders with a different key, it gets the instance of the new key, and the old one is kept. The
+/// instances are owned by the parent rather than by the components using them, so they survive a component that
+/// unmounts and remounts with the same key, and are only dropped together with the parent.
+pub fn use_tea_model_keyed<T: TeaModel + TeaModelInit>(key: impl Hash + Eq + Clone + 'static) -> TeaModelSignal<T> {
+    // the type of the keys is inferred from the insertion below
+    let registry = use_hook(|| {
+        try_consume_context::<TeaKeyed<_, T>>().unwrap_or_else(|| {
+            let scope = parent_scope().unwrap_or_else(current_scope_id);
+            let registry = TeaKeyed {
+                scope,
+                models: CopyValue::new_in_scope(HashMap::new(), scope),
+            };
+            Runtime::current().provide_context(scope, registry)
+        })
+    });
+
+    let mut models = registry.models;
+    let existing = models.peek().get(&key).copied();
+    existing.unwrap_or_else(|| {
+        let model = new_tea_model(registry.scope, T::init(), Box::new(apply), Queue::Unbounded);
+        models.write().insert(key, model);
+        model
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cmd;
+    use dioxus::prelude::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static MODELS: RefCell<Vec<TeaModelSignal<Sips>>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Sips {
+        count: u32,
+    }
+
+    impl TeaModel for Sips {
+        type Action = ();
+
+        fn update(&mut self, (): Self::Action) -> Cmd<Self::Action> {
+            self.count += 1;
+            Cmd::none()
+        }
+    }
+
+    #[component]
+    fn Cup(id: u8) -> Element {
+        let model = use_tea_model_keyed::<Sips>(id);
+        use_hook(|| MODELS.with_borrow_mut(|models| models.push(model)));
+        rsx! {}
+    }
+
+    #[test]
+    fn components_with_the_same_key_share_an_instance() {
+        fn app() -> Element {
+            rsx! {
+                Cup { id: 1 }
+                Cup { id: 2 }
+                Cup { id: 1 }
+            }
+        }
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+
+        let models = MODELS.take();
+        assert!(models[0] == models[2]);
+        assert!(models[0] != models[1], "Different keys get isolated instances");
+
+        dom.in_runtime(|| {
+            models[0].send_sync(());
+            assert_eq!(models[2].peek().count, 1);
+            assert_eq!(models[1].peek().count, 0);
+        });
+    }
+
+    /// A key whose instances all hash alike.
+    #[derive(Clone, PartialEq, Eq)]
+    struct Saucer(u8);
+
+    impl Hash for Saucer {
+        fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+    }
+
+    #[component]
+    fn SaucerCup(id: u8) -> Element {
+        let model = use_tea_model_keyed::<Sips>(Saucer(id));
+        use_hook(|| MODELS.with_borrow_mut(|models| models.push(model)));
+        rsx! {}
+    }
+
+    #[test]
+    fn colliding_keys_get_their_own_instances() {
+        fn app() -> Element {
+            rsx! {
+                SaucerCup { id: 1 }
+                SaucerCup { id: 2 }
+            }
+        }
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+
+        let models = MODELS.take();
+        assert!(models[0] != models[1], "Equal hashes do not make equal keys");
+    }
+}
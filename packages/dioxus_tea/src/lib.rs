@@ -2,67 +2,503 @@
 //! Example usage can be found in the `examples/tea-time` directory.
 //!
 //! Usage:
-//! ```rust,ignore
+//! ```rust
+//! use dioxus::prelude::*;
+//! use dioxus_tea::{use_tea_model, Cmd, TeaModel};
+//!
+//! #[derive(Default, Clone, PartialEq, Debug)]
+//! pub enum Status {
+//!     #[default]
+//!     FetchingCup,
+//!     EmptyCup,
+//!     TeaBag(TeaType),
+//!     Water(u8),
+//! }
+//!
+//! #[derive(Clone, PartialEq, Debug)]
+//! pub enum TeaType {
+//!     Black,
+//!     Green,
+//! }
+//!
 //! #[derive(Default, Clone, PartialEq)]
 //! pub struct AppState {
-//!    pub status: Status,
+//!     pub status: Status,
 //! }
 //!
 //! pub enum AppStatusUpdate {
 //!     CupFetched,
-//!     AddWater(u8),
 //!     AddTeaBag(TeaType),
-//!     Done,
+//!     AddWater(u8),
 //! }
 //!
 //! impl TeaModel for AppState {
 //!     type Action = AppStatusUpdate;
 //!
-//!     fn update(&mut self, action: Self::Action) {
+//!     fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
 //!         match action {
-//!            // handle actions and update the state accordingly
-//!            AppStatusUpdate::CupFetched => {
+//!             // handle actions and update the state accordingly
+//!             AppStatusUpdate::CupFetched => {
 //!                 // when the cup is fetched, we start with an empty cup
 //!                 self.status = Status::EmptyCup;
 //!             }
-//!             // other actions
+//!             AppStatusUpdate::AddTeaBag(tea_type) => self.status = Status::TeaBag(tea_type),
+//!             AppStatusUpdate::AddWater(temperature) => self.status = Status::Water(temperature),
 //!         }
+//!         Cmd::none()
 //!     }
 //! }
 //!
 //! #[component]
-//!  pub fn App() -> Element {
+//! pub fn App() -> Element {
 //!     let app_state = use_tea_model::<AppState>();
-//!     app_state.send(AppStatusUpdate::CupFetched);
+//!     use_hook(|| app_state.send(AppStatusUpdate::CupFetched));
+//!     rsx! { "{app_state.read().status:?}" }
 //! }
+//!
+//! // the reducer can be driven without a runtime, e.g. to check what the component will show
+//! let state = dioxus_tea::drive(
+//!     AppState::default(),
+//!     [AppStatusUpdate::CupFetched, AppStatusUpdate::AddTeaBag(TeaType::Green), AppStatusUpdate::AddWater(80)],
+//! );
+//! assert_eq!(state.status, Status::Water(80));
 //! ```
 
 #![warn(clippy::pedantic)]
 
+// lets the code generated by the derive macros refer to `::dioxus_tea` from within this crate as well
+#[cfg(feature = "derive")]
+extern crate self as dioxus_tea;
+
+mod actions;
+mod async_model;
+mod bounded;
+#[cfg(all(feature = "broadcast", target_arch = "wasm32"))]
+mod broadcast;
+mod bus;
+mod changed;
+mod changes;
+mod clock;
+mod cmd;
+mod combined;
+mod config;
+mod context;
+mod debounce;
+#[cfg(feature = "devtools")]
+mod devtools;
+#[cfg(feature = "diff")]
+mod diff;
+mod effect;
+mod error_boundary;
+mod fallible;
+mod global;
+mod history;
+#[cfg(feature = "serde")]
+mod json;
+mod keyed;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod middleware;
+mod notify;
+mod optimistic;
+mod panic;
+mod persist;
+mod priority;
+mod pure;
+mod read_only;
+mod recorder;
+mod registry;
+mod scheduled;
+mod source;
+mod state_machine;
+mod subscription;
+#[cfg(any(feature = "ws", feature = "broadcast"))]
+mod synced;
+#[cfg(test)]
+mod test_util;
+pub mod testing;
+mod threaded;
+mod throttle;
+#[cfg(feature = "tracing")]
+mod trace;
+mod transaction;
+mod transition;
+
+pub use async_model::{use_async_tea_model, AsyncTeaModel, AsyncTeaModelSignal};
+pub use bounded::{use_tea_model_bounded, SendError};
+#[cfg(all(feature = "broadcast", target_arch = "wasm32"))]
+pub use broadcast::{use_tea_model_broadcast, BroadcastChannel};
+pub use bus::{use_tea_bus, TeaBus};
+pub use changed::Changed;
+pub use clock::TeaClock;
+use cmd::Running;
+pub use cmd::{Cmd, IntoCmd};
+pub use combined::{use_tea_model_pair, TeaCombined, TeaCombinedAction};
+use config::Channel;
+pub use config::{use_tea_model_configured, TeaChannelConfig, TeaChannelMetrics, TeaOverflow};
+pub use context::{use_tea_consumer, use_tea_context, TeaProvider};
+pub use debounce::TeaDebounce;
+#[cfg(feature = "devtools")]
+pub use devtools::{use_tea_model_with_devtools, TeaDevtools};
+#[cfg(feature = "diff")]
+pub use diff::TeaDiff;
 use dioxus::{
-    hooks::UnboundedReceiver,
-    prelude::{use_coroutine, use_signal, Coroutine, ReadableExt, ReadableRef, Signal, WritableExt},
+    dioxus_core::{current_scope_id, use_drop, Runtime, ScopeId, Task},
+    prelude::{use_hook, CopyValue, ReadableExt, ReadableRef, Signal, WritableExt},
+};
+#[cfg(all(feature = "derive", feature = "diff"))]
+pub use dioxus_tea_macros::TeaDiff;
+#[cfg(feature = "derive")]
+pub use dioxus_tea_macros::{TeaActions, TeaLenses, TeaReducer};
+pub use effect::{use_tea_effect, use_tea_idle, use_tea_transition};
+pub use error_boundary::TeaErrorBoundary;
+pub use fallible::TryTeaModel;
+use futures_channel::{
+    mpsc::{self, UnboundedSender},
+    oneshot,
 };
-use futures_util::StreamExt;
+use futures_util::{stream, FutureExt, Stream, StreamExt};
+pub use global::use_global_tea_model;
+pub use history::{use_tea_model_with_history, TeaHistorySignal};
+#[cfg(feature = "serde")]
+pub use json::TeaActionJson;
+pub use keyed::use_tea_model_keyed;
+#[cfg(feature = "metrics")]
+pub use metrics::TeaMetrics;
+pub use middleware::{use_tea_model_with_middleware, Flow, TeaMiddleware};
+pub use notify::{use_tea_notifications, Notification, TeaNotifier};
+pub use panic::TeaPanicPolicy;
+pub use persist::{use_tea_model_persisted, TeaPersist};
+pub use priority::use_tea_model_prioritized;
+use priority::Priority;
+pub use pure::{use_pure_tea_model, PureTeaModel};
+pub use read_only::{use_tea_props, use_tea_value, use_tea_when, TeaLens, TeaReadOnlySignal};
+pub use recorder::{use_tea_model_recorded, TeaRecorder};
+pub use registry::{use_tea_registry, TeaRegistry};
+pub use scheduled::{IntervalHandle, ScheduledAction};
+pub use source::UNKNOWN_SOURCE;
+pub use state_machine::{use_tea_state_machine, IllegalTransition, TeaStateMachine, TransitionTable};
+use std::{
+    any::Any,
+    collections::VecDeque,
+    fmt,
+    future::{poll_fn, Future},
+    panic::AssertUnwindSafe,
+    rc::Rc,
+    task::Poll,
+};
+pub use subscription::{Subscription, TeaCoroutine};
+#[cfg(all(feature = "ws", not(target_arch = "wasm32")))]
+pub use synced::{use_tea_model_synced, WebSocket};
+#[cfg(any(feature = "ws", feature = "broadcast"))]
+pub use synced::{use_tea_model_synced_with, TeaConnection, TeaTransport};
+pub use threaded::{use_tea_model_on, use_tea_model_threaded, TeaWorker};
+use throttle::Throttle;
+#[cfg(feature = "tracing")]
+pub use trace::use_tea_model_traced;
+pub use transition::transition;
+
+/// Holds the (type-erased) error of the most recently processed action.
+type ErrorSink = Signal<Option<Rc<dyn Any>>>;
+
+/// Processes a single action against the model state and returns the resulting command.
+type Processor<T> = Box<dyn FnMut(&mut T, ErrorSink, <T as TeaModel>::Action) -> Cmd<<T as TeaModel>::Action>>;
+
+/// Passes a copy of an action and its source on to a stream; returns `false` once the stream has been dropped.
+type ActionObserver<A> = Box<dyn Fn(&A, &'static str) -> bool>;
+
+/// How the actions sent by components are queued.
+#[derive(Clone, Copy)]
+enum Queue {
+    /// On the unbounded channel that the actions of commands are queued on as well.
+    Unbounded,
+    /// On a queue of their own tuned by `config`, see [`use_tea_model_configured`].
+    Configured(TeaChannelConfig),
+}
+
+/// A message on the model's action queue.
+enum Envelope<A> {
+    Action(A),
+    Batch(Vec<A>),
+    /// An action followed by a callback that runs as soon as the action has been applied.
+    Then(A, Box<dyn FnOnce()>),
+    /// An action tagged with the source that sent it.
+    From(A, &'static str),
+    /// The actions of a transaction, followed by the callback that applies them and commits the result, if valid.
+    Transaction(Vec<A>, Box<dyn FnOnce(ScopeId, Vec<A>)>),
+}
+
+impl<A> Envelope<A> {
+    /// Returns the number of actions in this message.
+    fn len(&self) -> usize {
+        match self {
+            Envelope::Action(_) | Envelope::Then(..) | Envelope::From(..) => 1,
+            Envelope::Batch(actions) | Envelope::Transaction(actions, _) => actions.len(),
+        }
+    }
+}
 
 /// Trait representing a TEA model in Dioxus.
-pub trait TeaModel: 'static + Default + Clone + PartialEq {
+///
+/// A model does not need a `Default` state: models that are always seeded, e.g. from a config, are created with
+/// [`use_tea_model_with`]. Hooks that create the state themselves, like [`use_tea_model`], require
+/// [`TeaModelInit`] instead, which every `Default` model implements.
+pub trait TeaModel: 'static + Clone + PartialEq {
     /// The type of actions that can be processed by this model.
+    ///
+    /// Actions do not need to be `Clone`: they are moved from [`send`](TeaModelSignal::send) through the queue into
+    /// `update`, and never copied on the way. Only the opt-in hooks that keep or observe actions next to `update`,
+    /// like [`use_tea_model_recorded`] and [`action_stream`](TeaModelSignal::action_stream), require `Clone`; wrap
+    /// large payloads, like an image attached to an order, in an `Arc` to make those clones cheap as well.
     type Action;
 
     /// Updates the model state based on the provided action.
-    fn update(&mut self, action: Self::Action);
+    ///
+    /// The returned [`Cmd`] is run after the state has been updated; use [`Cmd::none`] when there is no follow-up work.
+    ///
+    /// `self` is the previous state until the reducer changes it, so transitions that depend on the current value,
+    /// like a hysteresis on the water temperature, can read it first and write afterwards, without a snapshot:
+    ///
+    /// ```rust,ignore
+    /// KettleAction::Measured(temperature) => {
+    ///     // only switch off once the water is well above the target, so the heater does not flicker
+    ///     if self.heating && temperature > self.target + 2 {
+    ///         self.heating = false;
+    ///     }
+    ///     self.temperature = temperature;
+    /// }
+    /// ```
+    fn update(&mut self, action: Self::Action) -> Cmd<Self::Action>;
+
+    /// Updates the model like [`update`](Self::update), and reports whether the update changed the state.
+    ///
+    /// The coroutine applies every action through this. When all the actions it applies at once report
+    /// [`Changed::No`], it neither compares the states with [`changed`](Self::changed) nor writes the state, so
+    /// subscribers do not re-render, even without [`use_tea_model_dedup`]. That saves the comparison of large
+    /// states for actions that a guard rejects. A model that overrides this usually moves its reducer here, and lets
+    /// `update` delegate to it, so both behave the same:
+    ///
+    /// ```rust,ignore
+    /// fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+    ///     self.update_checked(action).0
+    /// }
+    ///
+    /// fn update_checked(&mut self, action: Self::Action) -> (Cmd<Self::Action>, Changed) {
+    ///     // ...
+    ///     if self.status != Status::EmptyCup {
+    ///         return (Cmd::none(), Changed::No);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Reporting `No` for an update that did change the state discards the change. Models whose updates run
+    /// elsewhere, like those of [`use_tea_model_threaded`], always count as changed, and [`TryTeaModel`]s report
+    /// through [`try_update_checked`](TryTeaModel::try_update_checked) instead. Defaults to [`Changed::Yes`].
+    fn update_checked(&mut self, action: Self::Action) -> (Cmd<Self::Action>, Changed) {
+        (self.update(action), Changed::Yes)
+    }
+
+    /// Returns `true` if `action` resets the model, so the commands that are still running are no longer wanted.
+    ///
+    /// Before such an action is applied, every running command is cancelled, so a lingering "brew for 2 seconds"
+    /// cannot report `Done` after the user started over. State changes that were already applied are not rolled
+    /// back; only the pending futures are dropped. Defaults to `false` for every action.
+    fn cancels_effects(_action: &Self::Action) -> bool {
+        false
+    }
+
+    /// Reports an action that a guard of the reducer rejected, for observability.
+    ///
+    /// Expected outcomes, like adding a tea bag without a cup, are best modelled in the state, e.g. as an error
+    /// status. That leaves no trace of which guard failed, so reducers may call this hook from the branch that
+    /// rejects an action, and models override it to log or count the rejections:
+    ///
+    /// ```rust,ignore
+    /// AppStatusUpdate::AddTeaBag(ref tea_type) => match self.status {
+    ///     Status::EmptyCup => self.status = Status::TeaBag(tea_type.clone()),
+    ///     _ => {
+    ///         self.on_rejected(&action);
+    ///         self.status = Status::Error(MakeTeaError::CupNotEmpty);
+    ///     }
+    /// },
+    /// ```
+    ///
+    /// This complements the error sink of [`TryTeaModel`]: errors are exceptional failures that components render,
+    /// and that are cleared by the next successful action, while rejections are regular outcomes that only
+    /// observability tools care about. The hook runs synchronously within `update`, so it must not send actions of
+    /// its own. Defaults to doing nothing.
+    fn on_rejected(&self, _action: &Self::Action) {}
+
+    /// Decides how the model recovers after `update` panicked, given the panic message and the state from before
+    /// the action.
+    ///
+    /// The coroutine catches panics of `update`, so a single bad action does not stop the model from processing
+    /// the actions after it. The panic is still reported by the panic hook, which prints it to stderr by default.
+    /// Since the action is applied to a copy of the state, nothing it changed before panicking is kept, and its
+    /// commands are dropped; in a batch, the whole batch is dropped along with it. Defaults to
+    /// [`TeaPanicPolicy::Skip`].
+    ///
+    /// Panics can only be caught where they unwind. On `wasm32-unknown-unknown`, panics abort by default, so the
+    /// first panic ends the app no matter the policy, and the same goes for builds with `panic = "abort"`.
+    fn on_panic(&self, _message: &str) -> TeaPanicPolicy<Self> {
+        TeaPanicPolicy::Skip
+    }
+
+    /// Drops or merges redundant actions before they are applied.
+    ///
+    /// Right before applying queued actions, the coroutine passes every action that is already waiting in the
+    /// queue, in order, so the model can collapse e.g. a burst of identical temperature changes from a slider. Only
+    /// actions passed to [`TeaModelSignal::send`] and the ones produced by commands are coalesced; batches and
+    /// queries are always applied as they are, and mark the boundaries of what is passed. Defaults to keeping every
+    /// action.
+    fn coalesce(_pending: &mut VecDeque<Self::Action>) {}
+
+    /// Decides whether an update changed the state, given the state from before and after it.
+    ///
+    /// Every change detection of the model goes through this: [`use_tea_model_dedup`] skips writing back an
+    /// unchanged state, and [`last_changed`](TeaModelSignal::last_changed), [`changes`](TeaModelSignal::changes),
+    /// [`use_tea_transition`], persistence and history only react to changed states. Defaults to `PartialEq`,
+    /// which compares the whole state; big models can provide a cheaper comparison instead, e.g. of a revision that
+    /// every update that changes something bumps:
+    ///
+    /// ```rust,ignore
+    /// fn changed(old: &Self, new: &Self) -> bool {
+    ///     old.revision != new.revision
+    /// }
+    /// ```
+    ///
+    /// Returning `false` for a state that did change suppresses the notifications of that update: the dedup model
+    /// drops it, and subscribers and change streams never see it. So the comparison may be coarser than `PartialEq`,
+    /// reporting changes for equal states, but must never miss a real change.
+    fn changed(old: &Self, new: &Self) -> bool {
+        old != new
+    }
+
+    /// Removes consecutive duplicates from `pending`, for use in [`coalesce`](Self::coalesce):
+    ///
+    /// ```rust,ignore
+    /// fn coalesce(pending: &mut VecDeque<Self::Action>) {
+    ///     Self::dedup_adjacent(pending);
+    /// }
+    /// ```
+    fn dedup_adjacent(pending: &mut VecDeque<Self::Action>)
+    where
+        Self::Action: PartialEq,
+    {
+        let mut actions: Vec<_> = pending.drain(..).collect();
+        actions.dedup();
+        pending.extend(actions);
+    }
+
+    #[must_use]
+    /// Applies `actions` to `initial` in order, and returns the final state.
+    ///
+    /// The commands returned by `update` are dropped without running, so this is a pure function of the actions,
+    /// which makes it a good fit for property tests: generate arbitrary action sequences, e.g. with `proptest` or
+    /// `quickcheck`, and check an invariant of the final state. For instance, that tea is never ready without water
+    /// having been poured:
+    ///
+    /// ```rust,ignore
+    /// let mut poured = false;
+    /// let actions = actions.into_iter().inspect(|action| poured |= matches!(action, AppStatusUpdate::AddWater(_)));
+    /// let state = AppState::apply_all(AppState::default(), actions);
+    /// assert!(poured || state.status != Status::TeaReady);
+    /// ```
+    ///
+    /// Only the follow-ups of [`Cmd::dispatch_next`] are applied, right after the action that dispatched them, since
+    /// they are part of the same synchronous chain.
+    fn apply_all(mut initial: Self, actions: impl IntoIterator<Item = Self::Action>) -> Self {
+        for action in actions {
+            let _ = process_action(&mut initial, action);
+        }
+        initial
+    }
+
+    #[doc(hidden)]
+    /// Applies an action on behalf of the coroutine. Overridden by [`TryTeaModel`] to report errors.
+    fn try_apply(&mut self, action: Self::Action) -> Result<Cmd<Self::Action>, Rc<dyn Any>> {
+        let (cmd, changed) = self.update_checked(action);
+        changed::report(changed);
+        Ok(cmd)
+    }
+}
+
+/// The initial state of models that can be created without any input, like with [`use_tea_model`].
+///
+/// Every `Default` type implements this trait, starting from its default state, so it only needs to be implemented
+/// by hand for models without a meaningful `Default`.
+pub trait TeaModelInit {
+    /// Creates the initial state.
+    fn init() -> Self;
+}
+
+impl<T: Default> TeaModelInit for T {
+    fn init() -> Self {
+        T::default()
+    }
 }
 
 /// A signal that holds the state of a `TeaModel` and provides an internal coroutine for processing actions.
 #[derive(Clone, PartialEq)]
 pub struct TeaModelSignal<T: TeaModel> {
     inner: Signal<T>,
-    co: Coroutine<<T as TeaModel>::Action>,
+    error: ErrorSink,
+    process: CopyValue<Processor<T>>,
+    /// Pending timers of [`TeaModelSignal::send_debounced`], keyed by the model's (type-erased) debounce key.
+    debounced: CopyValue<Option<Box<dyn Any>>>,
+    throttle: CopyValue<Throttle<<T as TeaModel>::Action>>,
+    /// Timers of the actions scheduled with [`TeaModelSignal::send_after`].
+    scheduled: CopyValue<Vec<Task>>,
+    /// The clock that the timers of the model wait on, see [`TeaModelSignal::set_clock`].
+    clock: CopyValue<TeaClock>,
+    /// Number of actions that are queued but not yet applied.
+    queued: CopyValue<usize>,
+    /// Tasks of the commands that are still running.
+    running: CopyValue<Running>,
+    /// Whether updates that leave the state equal skip the write, see [`use_tea_model_dedup`].
+    dedup: CopyValue<bool>,
+    /// Ranks the queued actions of a model created with [`use_tea_model_prioritized`].
+    priority: CopyValue<Option<Priority<<T as TeaModel>::Action>>>,
+    /// Whether the most recently processed action changed the state.
+    last_changed: CopyValue<bool>,
+    /// Number of writes of the state, see [`TeaModelSignal::version`].
+    version: CopyValue<u64>,
+    /// Timing of the updates, see [`TeaModelSignal::metrics`].
+    #[cfg(feature = "metrics")]
+    metrics: CopyValue<TeaMetrics>,
+    /// Senders of the streams returned by [`TeaModelSignal::changes`].
+    changes: CopyValue<Vec<UnboundedSender<T>>>,
+    /// Waiters of [`TeaModelSignal::next_change`].
+    next_changes: CopyValue<Vec<oneshot::Sender<T>>>,
+    /// Listeners of the notifications emitted by `update`, see [`use_tea_notifications`].
+    notifications: CopyValue<Vec<UnboundedSender<String>>>,
+    /// Observers of the streams returned by [`TeaModelSignal::action_stream`].
+    observers: CopyValue<Vec<ActionObserver<<T as TeaModel>::Action>>>,
+    /// The queue of a model created with [`use_tea_model_configured`] or [`use_tea_model_bounded`].
+    channel: CopyValue<Option<Channel<<T as TeaModel>::Action>>>,
+    /// Sending half of the coroutine's (unbounded) queue.
+    tx: CopyValue<UnboundedSender<Envelope<<T as TeaModel>::Action>>>,
+    /// Spawns the coroutine of a model from [`use_tea_model_lazy`] that has not been woken yet.
+    idle: CopyValue<Option<Box<dyn FnOnce()>>>,
 }
 
 impl<T: TeaModel> Copy for TeaModelSignal<T> {}
 
+/// Prints the current state and the number of [pending](TeaModelSignal::pending_len) actions, e.g.
+/// `TeaModelSignal { state: Counter { count: 1 }, pending: 2, .. }`, without subscribing the current component.
+impl<T: TeaModel + fmt::Debug> fmt::Debug for TeaModelSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("TeaModelSignal");
+        match self.inner.try_peek() {
+            Ok(state) => debug.field("state", &*state),
+            Err(_) => debug.field("state", &format_args!("<dropped>")),
+        };
+        debug
+            .field("pending", &self.queued.try_peek().map_or(0, |queued| *queued))
+            .finish_non_exhaustive()
+    }
+}
+
 impl<T: TeaModel> TeaModelSignal<T> {
     #[must_use]
     /// Returns a reference to the underlying signal for reading the model state.
@@ -70,26 +506,1748 @@ impl<T: TeaModel> TeaModelSignal<T> {
         self.inner.read()
     }
 
+    #[must_use]
+    /// Returns a clone of the current model state, subscribing the current component like [`read`](Self::read).
+    ///
+    /// The owned snapshot can be held across an `await` or moved into a spawned task, where a borrow of the signal
+    /// would conflict with later writes. The whole state is cloned on every call, so prefer [`read`](Self::read)
+    /// for large states that are only inspected briefly.
+    pub fn read_owned(&self) -> T {
+        self.inner.read().clone()
+    }
+
+    #[must_use]
+    /// Returns a reference to the model state without subscribing the current component to changes.
+    ///
+    /// Use this in event handlers and other non-reactive contexts where reading the state should not cause a
+    /// re-render when it changes.
+    pub fn peek(&self) -> ReadableRef<'_, Signal<T>> {
+        self.inner.peek()
+    }
+
+    #[must_use]
+    /// Like [`read`](Self::read), but returns `None` instead of panicking when the state cannot be borrowed.
+    ///
+    /// That happens while the state is borrowed mutably, i.e. while the coroutine applies an action or a
+    /// [`with_mut`](Self::with_mut) closure runs, and once the component that owns the model has been dropped. A
+    /// write never outlives the callback that made it, so a callback that got `None` can simply try again later,
+    /// e.g. from the next render or event; it does not need to wait for anything.
+    pub fn try_read(&self) -> Option<ReadableRef<'_, Signal<T>>> {
+        self.inner.try_read().ok()
+    }
+
+    #[must_use]
+    /// Like [`peek`](Self::peek), but returns `None` instead of panicking when the state cannot be borrowed, see
+    /// [`try_read`](Self::try_read).
+    pub fn try_peek(&self) -> Option<ReadableRef<'_, Signal<T>>> {
+        self.inner.try_peek().ok()
+    }
+
     /// Sends an action to the coroutine for processing.
+    ///
+    /// Actions are applied in the order in which they were sent, no matter which component sent them: every `send`
+    /// is appended to a single FIFO queue, so a tea bag sent before the water is always added first. Actions
+    /// produced by commands are appended to the same queue when their command completes, and actions sent from an
+    /// effect, like [`use_tea_effect`], when the effect runs after the render; both queue up behind the actions that
+    /// were sent before. The one exception is a model created with [`use_tea_model_bounded`], whose commands bypass
+    /// the bound, so their actions are only ordered among themselves.
+    ///
+    /// For a model created with [`use_tea_model_bounded`], the action is dropped when the queue is full; use
+    /// [`try_send`](Self::try_send) or [`send_async`](Self::send_async) to handle that case.
+    ///
+    /// This is the fire-and-forget way to send an action: there is nothing to await or to ignore. The variants that
+    /// return a future, [`send_query`](Self::send_query) and [`dispatch`](Self::dispatch), are `#[must_use]`, so a
+    /// forgotten `.await` is reported; where that result is deliberately not wanted, call `send` instead.
     pub fn send(&self, action: T::Action) {
-        self.co.send(action);
+        let _ = self.enqueue(Envelope::Action(action));
+    }
+
+    /// Sends several actions to the coroutine, to be processed as a single message.
+    ///
+    /// All actions are applied in order under a single write of the state, so subscribers re-render only once for
+    /// the whole batch. The batch keeps its place in the queue: interleaving `send` and `send_batch` calls preserves
+    /// FIFO order.
+    pub fn send_batch(&self, actions: impl IntoIterator<Item = T::Action>) {
+        let _ = self.enqueue(Envelope::Batch(actions.into_iter().collect()));
+    }
+
+    /// Sends several actions to the coroutine, each as a message of its own, in order.
+    ///
+    /// Unlike [`send_batch`](Self::send_batch), which applies all actions under a single write, this is the same as
+    /// calling [`send`](Self::send) for every action: each of them is applied on its own, with its own write of the
+    /// state, change detection and commands, so [`changes`](Self::changes) and
+    /// [`action_stream`](Self::action_stream) see every step, and [`TeaModel::coalesce`] may merge them. That suits
+    /// replaying a log of recorded actions. Components still re-render once the queue has been worked off, not once
+    /// per action.
+    pub fn send_all(&self, actions: impl IntoIterator<Item = T::Action>) {
+        for action in actions {
+            self.send(action);
+        }
+    }
+
+    /// Sends the action returned by `predicate`, if any, deciding on the current state without subscribing the
+    /// current component.
+    ///
+    /// This replaces reading the state and sending an action in the caller, e.g. to only add water once the cup holds
+    /// a tea bag:
+    ///
+    /// ```rust,ignore
+    /// app_state.send_if(|state| matches!(state.status, Status::TeaBag(_)).then_some(AppStatusUpdate::AddWater(80)));
+    /// ```
+    ///
+    /// The predicate runs synchronously, on the state as it is at the time of the call. Actions that are still queued
+    /// are not applied yet, so the state may have moved on by the time the action is applied; for decisions on the
+    /// state that the action will actually see, check it in `update` instead.
+    pub fn send_if(&self, predicate: impl FnOnce(&T) -> Option<T::Action>) {
+        let action = predicate(&self.peek());
+        if let Some(action) = action {
+            self.send(action);
+        }
+    }
+
+    /// Sends the action built by `into_action` from an owned copy of `borrowed`.
+    ///
+    /// Actions wait in the queue and may be applied long after the caller returned, so they have to be `'static`,
+    /// and that does not change here: an action cannot hold on to borrowed data. This helper does the conversion that
+    /// is needed instead, e.g. for a temperature label borrowed from an input event:
+    ///
+    /// ```rust,ignore
+    /// app_state.send_owned(event.value().as_str(), KettleAction::Label);
+    /// ```
+    ///
+    /// The borrowed value is converted with [`ToOwned`] right away, before anything is queued, so it is free to go
+    /// out of scope as soon as this returns.
+    pub fn send_owned<B: ToOwned + ?Sized>(&self, borrowed: &B, into_action: impl FnOnce(B::Owned) -> T::Action) {
+        self.send(into_action(borrowed.to_owned()));
+    }
+
+    #[must_use]
+    /// Returns the number of actions that are queued but not yet applied.
+    ///
+    /// This includes the actions produced by commands that finished, but not the commands that are still running.
+    /// Reading the count does not subscribe the current component; since the state is written once the queue has
+    /// been worked off, components that read the state re-render when the model catches up.
+    pub fn pending_len(&self) -> usize {
+        *self.queued.peek()
+    }
+
+    #[must_use]
+    /// Returns `true` if the most recently processed action changed the state, by [`TeaModel::changed`].
+    ///
+    /// This is handy to only trigger an animation when the status genuinely advanced. A batch counts as a single
+    /// action. While many actions are in flight, the flag keeps moving on: by the time it is read, another action may
+    /// have been applied after the one of interest. To check a specific action, read the flag in the closure of
+    /// [`send_query`](Self::send_query), which runs right after that action has been applied. States written
+    /// directly, e.g. by [`with_mut`](Self::with_mut), do not affect the flag, and reading it does not subscribe the
+    /// current component.
+    pub fn last_changed(&self) -> bool {
+        *self.last_changed.peek()
+    }
+
+    #[must_use]
+    /// Returns the version of the state, which increases every time the state is written.
+    ///
+    /// This is meant for caches outside the model, like an expensive computation derived from the state: remember
+    /// the version it was computed for, and recompute once the version differs. Every processed action bumps the
+    /// version, even when it left the state equal, unless the model was created with [`use_tea_model_dedup`]; a
+    /// batch counts as a single write. The version starts at 0 and is not reset by [`reset`](Self::reset): a reset,
+    /// like any other write through [`with_mut`](Self::with_mut), bumps it as well, so a cache never mistakes the
+    /// reset state for one it has seen before. It wraps around after `u64::MAX` writes, which no model lives long
+    /// enough to reach. Reading the version does not subscribe the current component.
+    pub fn version(&self) -> u64 {
+        *self.version.peek()
+    }
+
+    #[must_use]
+    /// Returns `true` if there are no queued actions left to apply.
+    pub fn is_idle(&self) -> bool {
+        self.pending_len() == 0
+    }
+
+    #[must_use = "use `send` when the result is not needed"]
+    /// Sends an action to the coroutine and resolves with a value `extract`ed from the state right after the action
+    /// has been applied.
+    ///
+    /// The action is queued right away, when this method is called, and keeps its place in the queue; `extract`
+    /// sees the state before any later action is applied. When the queue of a model created with
+    /// [`use_tea_model_bounded`] is full, the returned future waits for room first, like
+    /// [`send_async`](Self::send_async).
+    ///
+    /// # Panics
+    ///
+    /// The returned future panics if the model's component unmounts before the action has been applied.
+    pub fn send_query<R: 'static>(
+        &self,
+        action: T::Action,
+        extract: impl FnOnce(&T) -> R + 'static,
+    ) -> impl Future<Output = R> {
+        let (tx, rx) = oneshot::channel();
+        let inner = self.inner;
+        let reply = move || {
+            let _ = tx.send(extract(&inner.peek()));
+        };
+
+        let model = *self;
+        let queued = self.enqueue(Envelope::Then(action, Box::new(reply)));
+        async move {
+            if let Err(envelope) = queued {
+                model.enqueue_async(envelope).await;
+            }
+            rx.await.expect("the model was dropped before the query was answered")
+        }
+    }
+
+    #[must_use = "use `send` when there is no need to wait for the action"]
+    /// Sends an action to the coroutine and resolves once it has been applied.
+    ///
+    /// Unlike [`send_query`](Self::send_query), this does not extract anything from the state. Awaiting it makes
+    /// tests deterministic, without sleeping until the coroutine has caught up. Like `send_query`, the action is
+    /// queued right away, when this method is called.
+    ///
+    /// # Panics
+    ///
+    /// The returned future panics if the model's component unmounts before the action has been applied.
+    pub fn dispatch(&self, action: T::Action) -> impl Future<Output = ()> {
+        self.send_query(action, |_| ())
+    }
+
+    /// Applies an action immediately, bypassing the coroutine.
+    ///
+    /// The state is updated before this method returns, which is handy in event handlers and tests that need to
+    /// observe the new state on the next line. The resulting [`Cmd`] is still spawned, and its actions are sent
+    /// through the coroutine as usual.
+    ///
+    /// Since actions passed to [`send`](Self::send) are queued, mixing both methods breaks action ordering: an
+    /// action applied with `send_sync` overtakes every action that is still waiting in the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is currently borrowed, or when called from a context where writing to a signal is not
+    /// allowed, such as while rendering a component.
+    pub fn send_sync(&self, action: T::Action) {
+        let cmd = self.process([action]);
+        self.spawn_cmd(self.inner.origin_scope(), cmd);
+    }
+
+    /// Resets the model to its initial state, see [`TeaModelInit`].
+    ///
+    /// This is meant for hard resets only: the state is overwritten directly, with a single write, so subscribers
+    /// re-render once, but `update` is not called, no command is run and no effect is triggered by an action.
+    /// Actions that are still queued and commands that are still running are not affected, and will be applied to
+    /// the reset state. Actions scheduled with [`send_after`](Self::send_after) are cancelled, though.
+    pub fn reset(&self)
+    where
+        T: TeaModelInit,
+    {
+        self.reset_with(T::init());
+    }
+
+    /// Resets the model to `state`, bypassing `update` just like [`reset`](Self::reset).
+    pub fn reset_with(&self, state: T) {
+        self.cancel_scheduled();
+        self.with_mut(|current| *current = state);
+    }
+
+    #[must_use]
+    /// Creates an independent model, seeded with a clone of the current state, for "what-if" previews.
+    ///
+    /// The fork has a coroutine of its own and applies actions with plain `update`: changes to it never propagate
+    /// back, and the middleware, history or persistence of this model are not carried over. To commit a preview,
+    /// [`reset_with`](Self::reset_with) this model to the state of the fork.
+    ///
+    /// Like a model from [`use_tea_model`], the fork is owned by the current component and dropped along with it,
+    /// and every call creates a new one, so fork either once, e.g. `use_hook(|| app_state.fork())`, or from an event
+    /// handler. A fork that should outlive the component belongs in a component of its own higher up, or in a global
+    /// model seeded with [`reset_with`](Self::reset_with).
+    pub fn fork(&self) -> TeaModelSignal<T> {
+        new_tea_model(
+            current_scope_id(),
+            self.peek().clone(),
+            Box::new(apply),
+            Queue::Unbounded,
+        )
+    }
+
+    /// Stops the model's coroutine for good, e.g. for a controlled teardown in tests.
+    ///
+    /// This closes the action queue: the actions that are already queued are still applied, after which the
+    /// coroutine returns, and every later [`send`](Self::send) is silently dropped, like the actions of commands
+    /// that complete afterwards. Running commands and scheduled actions are cancelled. The state stays readable,
+    /// and the model is dropped as usual, together with its component.
+    pub fn shutdown(&self) {
+        self.cancel_scheduled();
+        let mut running = self.running;
+        running.write().cancel_all();
+        let mut channel = self.channel;
+        if let Some(channel) = channel.write().as_mut() {
+            channel.close();
+        }
+        self.tx.peek().close_channel();
+    }
+
+    /// Mutates the state directly with `f`, notifying subscribers once.
+    ///
+    /// **This breaks the unidirectional data flow of TEA**: `update` and any middleware, history or persistence
+    /// hooks are bypassed, so the change is not recorded, validated or saved, and no command is run. Use it
+    /// sparingly, for the rare state that is owned elsewhere, like a field synced from props; define an action for
+    /// everything else.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is currently borrowed.
+    pub fn with_mut(&self, f: impl FnOnce(&mut T)) {
+        let mut inner = self.inner;
+        self.bump_version();
+        if self.changes.peek().is_empty() && self.next_changes.peek().is_empty() {
+            f(&mut inner.write());
+            return;
+        }
+
+        let previous = inner.peek().clone();
+        f(&mut inner.write());
+        if T::changed(&previous, &inner.peek()) {
+            self.notify_changes();
+        }
     }
+
+    /// Runs actions through the processor, applying all of them under a single write of the state.
+    ///
+    /// An action for which [`TeaModel::cancels_effects`] returns `true` cancels the running commands, as well as the
+    /// commands of the actions before it.
+    ///
+    /// The actions are applied to a copy of the state, which is compared to the previous state to tell whether
+    /// they [changed](Self::last_changed) it.
+    fn process(&self, actions: impl IntoIterator<Item = T::Action>) -> Cmd<T::Action> {
+        let mut state = self.inner.peek().clone();
+        let (cmd, changed) = self.process_into(&mut state, actions);
+        if changed == Changed::No {
+            let mut last_changed = self.last_changed;
+            last_changed.set(false);
+        } else {
+            self.commit(state);
+        }
+        cmd
+    }
+
+    /// Writes a new state, unless it did not change and the model skips unchanged states, and notifies the streams
+    /// of changes.
+    fn commit(&self, state: T) {
+        let (mut inner, mut last_changed) = (self.inner, self.last_changed);
+        let changed = T::changed(&inner.peek(), &state);
+        if changed || !*self.dedup.peek() {
+            inner.set(state);
+            self.bump_version();
+        }
+        last_changed.set(changed);
+        if changed {
+            self.notify_changes();
+        }
+    }
+
+    /// Counts a write of the state, see [`version`](Self::version).
+    fn bump_version(&self) {
+        let mut version = self.version;
+        let next = version.peek().wrapping_add(1);
+        version.set(next);
+    }
+
+    /// Runs actions through the processor, applying them to `state`, and returns their commands along with whether
+    /// any of them [changed](TeaModel::update_checked) the state.
+    fn process_into(&self, state: &mut T, actions: impl IntoIterator<Item = T::Action>) -> (Cmd<T::Action>, Changed) {
+        let mut processor = self.process;
+        let mut processor = processor.write();
+
+        let (mut cmds, mut changed) = (Vec::new(), Changed::No);
+        for action in actions {
+            let apply = |state: &mut T, action| {
+                self.observe(&action);
+                #[cfg(feature = "metrics")]
+                let start = web_time::Instant::now();
+                // the state is rolled back below when `update` panics, so it is never observed half-updated
+                let ((applied, emitted), reported) = changed::collect(|| {
+                    notify::collect(|| {
+                        std::panic::catch_unwind(AssertUnwindSafe(|| processor(state, self.error, action)))
+                    })
+                });
+                if reported == Changed::Yes {
+                    changed = Changed::Yes;
+                }
+                #[cfg(feature = "metrics")]
+                {
+                    let mut metrics = self.metrics;
+                    metrics.write().record(start.elapsed());
+                }
+
+                match applied {
+                    Ok(cmd) => {
+                        self.notify(&emitted);
+                        Some(cmd)
+                    }
+                    Err(payload) => {
+                        let policy = self.inner.peek().on_panic(panic::message(&*payload));
+                        match policy {
+                            TeaPanicPolicy::Skip => *state = self.inner.peek().clone(),
+                            TeaPanicPolicy::Restart(restarted) => {
+                                *state = restarted;
+                                let mut running = self.running;
+                                running.write().cancel_all();
+                            }
+                            TeaPanicPolicy::Halt => std::panic::resume_unwind(payload),
+                        }
+                        None
+                    }
+                }
+            };
+            // an action that cancels effects drops the commands of the whole batch so far, not only of its chain
+            let cancel = || {
+                cmds.clear();
+                let mut running = self.running;
+                running.write().cancel_all();
+            };
+            match process_chain(state, action, apply, cancel) {
+                Some(cmd) => cmds.push(cmd),
+                None => return (Cmd::none(), Changed::Yes),
+            }
+        }
+        (Cmd::batch(cmds), changed)
+    }
+
+    /// Applies the queued `actions` one by one, after letting the model [`coalesce`](TeaModel::coalesce) them and
+    /// sorting them by priority, if any, and spawns the resulting commands.
+    fn flush(&self, scope: ScopeId, actions: &mut VecDeque<T::Action>) {
+        if actions.is_empty() {
+            return;
+        }
+        T::coalesce(actions);
+        if let Some(priority) = &*self.priority.peek() {
+            priority::prioritize(actions, priority);
+        }
+        for action in actions.drain(..) {
+            let cmd = self.process([action]);
+            self.spawn_cmd(scope, cmd);
+        }
+    }
+
+    /// Spawns `cmd` in `scope`, queueing the actions it produces on the coroutine.
+    fn spawn_cmd(&self, scope: ScopeId, cmd: Cmd<T::Action>) {
+        self.wake();
+        let coroutine = self.coroutine();
+        cmd.spawn(scope, self.running, move |action| coroutine.send(action));
+    }
+
+    /// Queues a message on the coroutine, counting its actions as pending.
+    ///
+    /// Gives the message back when the queue of a bounded model is full.
+    fn enqueue(&self, envelope: Envelope<T::Action>) -> Result<(), Envelope<T::Action>> {
+        self.wake();
+        let len = envelope.len();
+        let mut channel = self.channel;
+        // the queue is gone along with the component that owned the model
+        let Ok(mut channel) = channel.try_write() else {
+            return Ok(());
+        };
+        if let Some(channel) = channel.as_mut() {
+            let dropped = channel.push(envelope)?.as_ref().map_or(0, Envelope::len);
+            let mut queued = self.queued;
+            let pending = (*queued.peek() + len).saturating_sub(dropped);
+            queued.set(pending);
+            return Ok(());
+        }
+        drop(channel);
+        // a model that was shut down drops every message
+        if self.tx.peek().unbounded_send(envelope).is_err() {
+            return Ok(());
+        }
+        let mut queued = self.queued;
+        *queued.write() += len;
+        Ok(())
+    }
+
+    /// Queues a message on the coroutine, waiting for room when the queue of a bounded model is full.
+    ///
+    /// The message is dropped if the model's component unmounts while waiting.
+    async fn enqueue_async(&self, envelope: Envelope<T::Action>) {
+        self.wake();
+        let mut channel = self.channel;
+        if channel.peek().is_some() {
+            poll_fn(|cx| match channel.try_write().as_deref_mut() {
+                Ok(Some(channel)) => channel.poll_ready(cx),
+                _ => Poll::Ready(()),
+            })
+            .await;
+        }
+        let _ = self.enqueue(envelope);
+    }
+
+    /// Spawns the coroutine of a lazy model, unless it is running already.
+    fn wake(&self) {
+        let mut idle = self.idle;
+        // the flag is only ever touched on the thread that owns the model, since the model is not `Send`
+        let spawn = idle.try_write().ok().and_then(|mut idle| idle.take());
+        if let Some(spawn) = spawn {
+            spawn();
+        }
+    }
+
+    /// Spawns a task that lives as long as the component that owns the model.
+    fn spawn(&self, future: impl Future<Output = ()> + 'static) -> Task {
+        Runtime::current().spawn(self.inner.origin_scope(), future)
+    }
+}
+
+#[must_use]
+/// Applies `actions` to `initial` synchronously, and returns the final state.
+///
+/// This is [`TeaModel::apply_all`] as a function of its own, for examples and quick checks that should not need a
+/// runtime: commands are dropped without running, so only the reducer is exercised. For the effects as well, use
+/// [`TeaTestHarness`](testing::TeaTestHarness).
+pub fn drive<T: TeaModel>(initial: T, actions: impl IntoIterator<Item = T::Action>) -> T {
+    T::apply_all(initial, actions)
+}
+
+/// Applies `action` to `state`, followed by the follow-ups it [dispatches](Cmd::dispatch_next), and returns the
+/// commands of all of them.
+///
+/// This is the step that every driver of a model repeats for each action, without any of the signals, queues or
+/// tasks of the coroutine, so it can be called from plain unit tests, and is the seam for custom drivers, e.g. one
+/// that applies the actions of a server-side session. Such a driver owns the state, takes the actions as they
+/// arrive, cancels its running effects first for an action that [`cancels_effects`](TeaModel::cancels_effects),
+/// calls `process_action`, and runs the [effects](Cmd::into_effects) of the returned command, applying the actions
+/// they resolve to in turn:
+///
+/// ```rust,ignore
+/// while let Some(action) = actions.next().await {
+///     if AppState::cancels_effects(&action) {
+///         effects.clear();
+///     }
+///     effects.extend(process_action(&mut state, action).into_effects());
+/// }
+/// ```
+///
+/// Within the chain, a follow-up that cancels effects drops the commands of the actions before it. The errors of a
+/// [`TryTeaModel`] are discarded, like the notifications of a [`TeaNotifier`]. The coroutine of [`use_tea_model`]
+/// runs the same loop for every action, passing each action of the chain through the model's processor, so that
+/// middleware, guards and observers see the follow-ups as well; so do [`use_tea_model_threaded`],
+/// [`TeaModel::apply_all`], [`drive`], [transactions](TeaModelSignal::transaction) and the
+/// [`TeaTestHarness`](testing::TeaTestHarness).
+pub fn process_action<T: TeaModel>(state: &mut T, action: T::Action) -> Cmd<T::Action> {
+    process_chain(
+        state,
+        action,
+        |state, action| Some(state.try_apply(action).unwrap_or_default()),
+        || {},
+    )
+    .unwrap_or_default()
+}
+
+/// The loop behind [`process_action`], for drivers that need more control over the chain: `apply` applies every
+/// action of the chain and ends it by returning `None`, and `cancel` runs before every action that
+/// [cancels effects](TeaModel::cancels_effects), to drop the effects the driver is still holding on to.
+pub(crate) fn process_chain<T: TeaModel>(
+    state: &mut T,
+    action: T::Action,
+    mut apply: impl FnMut(&mut T, T::Action) -> Option<Cmd<T::Action>>,
+    mut cancel: impl FnMut(),
+) -> Option<Cmd<T::Action>> {
+    let mut cmds = Vec::new();
+    let mut follow_ups = VecDeque::from([action]);
+    while let Some(action) = follow_ups.pop_front() {
+        if T::cancels_effects(&action) {
+            cmds.clear();
+            cancel();
+        }
+        let mut cmd = apply(state, action)?;
+        follow_ups.extend(cmd.take_next());
+        cmds.push(cmd);
+    }
+    Some(Cmd::batch(cmds))
 }
 
 #[must_use]
 /// Creates a new `TeaModelSignal` for the given `TeaModel`.
-pub fn use_tea_model<T: TeaModel>() -> TeaModelSignal<T> {
-    let mut inner = use_signal(|| T::default());
-
-    let co = use_coroutine(move |mut rx: UnboundedReceiver<T::Action>| async move {
-        loop {
-            if let Some(action) = rx.next().await {
-                inner.with_mut(|me| {
-                    me.update(action);
+pub fn use_tea_model<T: TeaModel + TeaModelInit>() -> TeaModelSignal<T> {
+    use_tea_model_with(T::init)
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel` and dispatches `initial` once.
+///
+/// The action is queued on the first render only, after the coroutine has been created, so it is never dropped and
+/// never sent twice, no matter how often the component re-renders. Because the coroutine only runs once the render
+/// has completed, the first paint always shows the default state; the state produced by `initial` follows right
+/// after.
+pub fn use_tea_model_init<T: TeaModel + TeaModelInit>(initial: T::Action) -> TeaModelSignal<T> {
+    let model = use_tea_model::<T>();
+    use_hook(move || model.send(initial));
+    model
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, seeded with the state returned by `init`.
+///
+/// Like the initializer of `use_signal`, `init` only runs once, on the first render of the component.
+pub fn use_tea_model_with<T: TeaModel>(init: impl FnOnce() -> T) -> TeaModelSignal<T> {
+    use_tea_model_processed(init, || Box::new(apply))
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, which only notifies subscribers when an update changed the
+/// state.
+///
+/// A plain model marks its state as written for every action, so subscribers re-render even when `update` returned
+/// early without changing anything, e.g. for an invalid action. This model only writes the updated state back when
+/// it differs from the current state by [`TeaModel::changed`], so effects and subscribers only run for actual
+/// changes.
+pub fn use_tea_model_dedup<T: TeaModel + TeaModelInit>() -> TeaModelSignal<T> {
+    use_hook(|| {
+        let model = new_tea_model(current_scope_id(), T::init(), Box::new(apply), Queue::Unbounded);
+        let mut dedup = model.dedup;
+        dedup.set(true);
+        model
+    })
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, calling `on_drop` with the final state when the component
+/// that owns the model unmounts, e.g. to save it.
+///
+/// The handler is registered with `use_drop`, and runs while the component is torn down: the coroutine has stopped
+/// polling, so the state it is passed is the one of the last processed action. Actions still waiting in the queue are
+/// dropped along with the coroutine, and are neither applied nor passed to `on_drop`; later calls to
+/// [`send`](TeaModelSignal::send), e.g. by a command or a timer that outlived the component, are no-ops.
+pub fn use_tea_model_with_cleanup<T: TeaModel + TeaModelInit>(on_drop: impl FnOnce(&T) + 'static) -> TeaModelSignal<T> {
+    let model = use_tea_model::<T>();
+    use_drop(move || on_drop(&model.peek()));
+    model
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, which only spawns its coroutine on the first `send`.
+///
+/// This is meant for models that are created by many components but rarely used, like the state of a row in a
+/// large list: until an action arrives, the model is just its state, without a task of its own. Reads return the
+/// initial state as usual. The first message of any kind wakes the model, including a scheduled action or a
+/// subscription item that comes due, and from then on it works like a model from [`use_tea_model`].
+///
+/// Like every model, it is bound to the thread of its component, so waking it needs no synchronization.
+pub fn use_tea_model_lazy<T: TeaModel + TeaModelInit>() -> TeaModelSignal<T> {
+    use_hook(|| new_lazy_tea_model(current_scope_id(), T::init(), Box::new(apply), Queue::Unbounded))
+}
+
+/// Creates a new `TeaModelSignal` whose actions are handled by the processor returned from `processor`.
+///
+/// Both `init` and `processor` only run on the first render. This is the extension point for hooks that need to run
+/// code around every update; they typically wrap [`apply`].
+fn use_tea_model_processed<T: TeaModel>(
+    init: impl FnOnce() -> T,
+    processor: impl FnOnce() -> Processor<T>,
+) -> TeaModelSignal<T> {
+    use_tea_model_queued(init, processor, Queue::Unbounded)
+}
+
+/// Like [`use_tea_model_processed`], queueing the actions sent by components as `queue` tells.
+fn use_tea_model_queued<T: TeaModel>(
+    init: impl FnOnce() -> T,
+    processor: impl FnOnce() -> Processor<T>,
+    queue: Queue,
+) -> TeaModelSignal<T> {
+    use_hook(|| new_tea_model(current_scope_id(), init(), processor(), queue))
+}
+
+/// Creates a new `TeaModelSignal` owned by `scope`, together with the coroutine that processes its actions.
+///
+/// The state and the coroutine live as long as `scope` does. The actions sent by components are queued as `queue`
+/// tells.
+fn new_tea_model<T: TeaModel>(scope: ScopeId, init: T, processor: Processor<T>, queue: Queue) -> TeaModelSignal<T> {
+    let model = new_lazy_tea_model(scope, init, processor, queue);
+    model.wake();
+    model
+}
+
+/// Like [`new_tea_model`], only spawning the coroutine once the model is woken by its first message.
+fn new_lazy_tea_model<T: TeaModel>(
+    scope: ScopeId,
+    init: T,
+    processor: Processor<T>,
+    queue: Queue,
+) -> TeaModelSignal<T> {
+    let (tx, rx) = mpsc::unbounded::<Envelope<T::Action>>();
+    let channel = match queue {
+        Queue::Unbounded => None,
+        Queue::Configured(config) => Some(Channel::new(config)),
+    };
+
+    let model = TeaModelSignal {
+        inner: Signal::new_in_scope(init, scope),
+        error: Signal::new_in_scope(None, scope),
+        process: CopyValue::new_in_scope(processor, scope),
+        debounced: CopyValue::new_in_scope(None, scope),
+        throttle: CopyValue::new_in_scope(Throttle::default(), scope),
+        scheduled: CopyValue::new_in_scope(Vec::new(), scope),
+        clock: CopyValue::new_in_scope(TeaClock::system(), scope),
+        queued: CopyValue::new_in_scope(0, scope),
+        running: CopyValue::new_in_scope(Running::default(), scope),
+        dedup: CopyValue::new_in_scope(false, scope),
+        priority: CopyValue::new_in_scope(None, scope),
+        last_changed: CopyValue::new_in_scope(false, scope),
+        version: CopyValue::new_in_scope(0, scope),
+        #[cfg(feature = "metrics")]
+        metrics: CopyValue::new_in_scope(TeaMetrics::default(), scope),
+        changes: CopyValue::new_in_scope(Vec::new(), scope),
+        next_changes: CopyValue::new_in_scope(Vec::new(), scope),
+        notifications: CopyValue::new_in_scope(Vec::new(), scope),
+        observers: CopyValue::new_in_scope(Vec::new(), scope),
+        channel: CopyValue::new_in_scope(channel, scope),
+        tx: CopyValue::new_in_scope(tx, scope),
+        idle: CopyValue::new_in_scope(None, scope),
+    };
+
+    // actions produced by commands are appended to the same queue as the ones sent by components, which keeps the
+    // processing order strictly FIFO; only those of bounded models end up on a queue of their own
+    let mut channel = model.channel;
+    let channel_rx = stream::poll_fn(move |cx| match channel.try_write().as_deref_mut() {
+        Ok(Some(channel)) => channel.poll_next(cx),
+        _ => Poll::Ready(None),
+    });
+    let messages = stream::select(rx, channel_rx);
+    let mut idle = model.idle;
+    idle.set(Some(Box::new(move || {
+        Runtime::current().spawn(scope, async move { work_off(&model, scope, messages).await });
+    })));
+
+    model
+}
+
+/// Applies the messages of the model's queue as they arrive, until every sender is gone or the queue is closed.
+async fn work_off<T: TeaModel>(
+    model: &TeaModelSignal<T>,
+    scope: ScopeId,
+    mut messages: impl Stream<Item = Envelope<T::Action>> + Unpin,
+) {
+    let mut queued = model.queued;
+    while let Some(message) = messages.next().await {
+        // take every message that is already waiting as well, so the model can coalesce queued actions
+        let mut ready = vec![message];
+        while let Some(Some(message)) = messages.next().now_or_never() {
+            ready.push(message);
+        }
+        let len: usize = ready.iter().map(Envelope::len).sum();
+
+        let mut actions = VecDeque::new();
+        for message in ready {
+            match message {
+                Envelope::Action(action) => actions.push_back(action),
+                Envelope::Batch(batch) => {
+                    model.flush(scope, &mut actions);
+                    model.spawn_cmd(scope, model.process(batch));
+                }
+                Envelope::Then(action, then) => {
+                    model.flush(scope, &mut actions);
+                    let cmd = model.process([action]);
+                    then();
+                    model.spawn_cmd(scope, cmd);
+                }
+                Envelope::From(action, source) => {
+                    model.flush(scope, &mut actions);
+                    let cmd = source::with_source(source, || model.process([action]));
+                    model.spawn_cmd(scope, cmd);
+                }
+                Envelope::Transaction(batch, commit) => {
+                    model.flush(scope, &mut actions);
+                    commit(scope, batch);
+                }
+            }
+        }
+        model.flush(scope, &mut actions);
+
+        let pending = queued.peek().saturating_sub(len);
+        queued.set(pending);
+    }
+}
+
+/// Applies an action to the model state, routing a failure to the error sink.
+fn apply<T: TeaModel>(state: &mut T, mut error: ErrorSink, action: T::Action) -> Cmd<T::Action> {
+    match state.try_apply(action) {
+        Ok(cmd) => {
+            if error.peek().is_some() {
+                error.set(None);
+            }
+            cmd
+        }
+        Err(err) => {
+            error.set(Some(err));
+            Cmd::none()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{mount, mount_model, run};
+    use dioxus::{
+        dioxus_core::{generation, needs_update},
+        prelude::*,
+    };
+    use std::time::Duration;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Counter {
+        count: i32,
+    }
+
+    enum CounterAction {
+        Increment,
+        Double,
+    }
+
+    impl TeaModel for Counter {
+        type Action = CounterAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                CounterAction::Increment => self.count += 1,
+                CounterAction::Double => self.count *= 2,
+            }
+            Cmd::none()
+        }
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Kettle {
+        temperature: u8,
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Thermometer {
+        label: String,
+    }
+
+    enum ThermometerAction {
+        Label(String),
+    }
+
+    impl TeaModel for Thermometer {
+        type Action = ThermometerAction;
+
+        fn update(&mut self, ThermometerAction::Label(label): Self::Action) -> Cmd<Self::Action> {
+            self.label = label;
+            Cmd::none()
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct TooHot(u8);
+
+    impl TryTeaModel for Kettle {
+        type Action = u8;
+        type Error = TooHot;
+
+        fn try_update(&mut self, temperature: Self::Action) -> Result<Cmd<Self::Action>, Self::Error> {
+            if temperature > 100 {
+                return Err(TooHot(temperature));
+            }
+            self.temperature = temperature;
+            Ok(Cmd::none())
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_updates_are_routed_to_the_error_sink() {
+        let (mut dom, kettle) = mount_model(use_tea_model::<Kettle>);
+
+        dom.in_runtime(|| kettle.send(120));
+        run(&mut dom).await;
+        dom.in_runtime(|| {
+            assert_eq!(kettle.errors().as_deref(), Some(&TooHot(120)));
+            assert_eq!(
+                kettle.read().temperature,
+                0,
+                "A failed update leaves the state untouched"
+            );
+            kettle.send(80);
+        });
+
+        run(&mut dom).await;
+        dom.in_runtime(|| {
+            assert_eq!(kettle.errors(), None, "A successful update clears the error");
+            assert_eq!(kettle.read().temperature, 80);
+        });
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Strainer {
+        revision: u32,
+        leaves: u8,
+    }
+
+    impl TryTeaModel for Strainer {
+        type Action = u8;
+        type Error = TooHot;
+
+        fn try_update(&mut self, leaves: Self::Action) -> Result<Cmd<Self::Action>, Self::Error> {
+            self.try_update_checked(leaves).map(|(cmd, _)| cmd)
+        }
+
+        fn try_update_checked(&mut self, leaves: Self::Action) -> Result<(Cmd<Self::Action>, Changed), Self::Error> {
+            if leaves == 0 {
+                return Ok((Cmd::none(), Changed::No));
+            }
+            // a few stray leaves are not worth a revision
+            self.leaves += leaves;
+            if leaves >= 3 {
+                self.revision += 1;
+            }
+            Ok((Cmd::none(), Changed::Yes))
+        }
+
+        fn changed(old: &Self, new: &Self) -> bool {
+            old.revision != new.revision
+        }
+    }
+
+    #[test]
+    fn fallible_models_supply_their_own_change_detection() {
+        let (dom, strainer) = mount_model(use_tea_model::<Strainer>);
+        dom.in_runtime(|| {
+            strainer.send_sync(0);
+            assert_eq!(strainer.version(), 0, "Unchanged reports skip the write");
+            strainer.send_sync(1);
+            assert!(!strainer.last_changed(), "The revision decides what counts as a change");
+            strainer.send_sync(3);
+            assert!(strainer.last_changed());
+        });
+    }
+
+    #[test]
+    fn send_sync_applies_the_action_immediately() {
+        let (dom, model) = mount_model(use_tea_model::<Counter>);
+        dom.in_runtime(|| {
+            model.send_sync(CounterAction::Increment);
+            assert_eq!(model.read().count, 1);
+        });
+    }
+
+    #[tokio::test]
+    async fn send_all_applies_every_action_on_its_own() {
+        let (mut dom, model) = mount_model(use_tea_model::<Counter>);
+        let mut changes = dom.in_runtime(|| model.changes());
+        dom.in_runtime(|| {
+            model.send_all([CounterAction::Increment, CounterAction::Double]);
+            model.send_batch([CounterAction::Increment, CounterAction::Double]);
+        });
+        run(&mut dom).await;
+
+        let counts: Vec<_> = std::iter::from_fn(|| changes.next().now_or_never().flatten())
+            .map(|counter| counter.count)
+            .collect();
+        assert_eq!(counts, [1, 2, 6], "Only the batch is applied under a single write");
+    }
+
+    #[tokio::test]
+    async fn send_if_decides_on_the_current_state() {
+        let (mut dom, model) = mount_model(use_tea_model::<Counter>);
+        dom.in_runtime(|| {
+            model.send(CounterAction::Increment);
+            model.send_if(|counter| (counter.count > 0).then_some(CounterAction::Double));
+        });
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| model.peek().count),
+            1,
+            "The queued increment is not seen yet"
+        );
+
+        dom.in_runtime(|| model.send_if(|counter| (counter.count > 0).then_some(CounterAction::Double)));
+        run(&mut dom).await;
+        assert_eq!(dom.in_runtime(|| model.peek().count), 2);
+    }
+
+    #[tokio::test]
+    async fn borrowed_input_is_owned_before_it_is_queued() {
+        let (mut dom, thermometer) = mount_model(use_tea_model::<Thermometer>);
+        dom.in_runtime(|| {
+            let input = String::from("80 °C");
+            thermometer.send_owned(input.as_str(), ThermometerAction::Label);
+            // the input is gone long before the action is applied
+            drop(input);
+        });
+        run(&mut dom).await;
+        assert_eq!(dom.in_runtime(|| thermometer.peek().label.clone()), "80 °C");
+    }
+
+    #[tokio::test]
+    async fn forks_are_independent_of_the_model() {
+        let (mut dom, model) = mount_model(use_tea_model::<Counter>);
+        dom.in_runtime(|| model.send(CounterAction::Increment));
+        run(&mut dom).await;
+
+        let fork = dom.in_scope(ScopeId::APP, || model.fork());
+        dom.in_runtime(|| {
+            fork.send(CounterAction::Double);
+            model.send(CounterAction::Increment);
+        });
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| (model.peek().count, fork.peek().count)), (2, 2));
+        dom.in_runtime(|| fork.send(CounterAction::Double));
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| model.peek().count),
+            2,
+            "Changes to the fork do not propagate back"
+        );
+    }
+
+    #[tokio::test]
+    async fn cleanup_sees_the_final_state() {
+        thread_local! {
+            static SAVED: std::cell::Cell<Option<i32>> = const { std::cell::Cell::new(None) };
+        }
+
+        #[component]
+        fn Child(slot: Signal<Option<TeaModelSignal<Counter>>>) -> Element {
+            let model = use_tea_model_with_cleanup::<Counter>(|counter| SAVED.set(Some(counter.count)));
+            use_hook(move || slot.set(Some(model)));
+            rsx! {}
+        }
+
+        fn app() -> Element {
+            let shown = use_signal(|| true);
+            let slot = use_signal(|| None);
+            use_context_provider(|| (shown, slot));
+            rsx! {
+                if shown() {
+                    Child { slot }
+                }
+            }
+        }
+
+        let (mut dom, (mut shown, slot)) = mount::<(Signal<bool>, Signal<Option<TeaModelSignal<Counter>>>)>(app);
+        let model = dom.in_runtime(|| slot.peek().unwrap());
+        dom.in_runtime(|| {
+            model.send(CounterAction::Increment);
+            model.send(CounterAction::Double);
+        });
+        run(&mut dom).await;
+        assert_eq!(SAVED.get(), None);
+
+        dom.in_runtime(|| shown.set(false));
+        run(&mut dom).await;
+        assert_eq!(SAVED.get(), Some(2));
+
+        dom.in_runtime(|| model.send(CounterAction::Increment));
+        run(&mut dom).await;
+        assert_eq!(SAVED.get(), Some(2), "Sending to a dropped model is a no-op");
+    }
+
+    #[tokio::test]
+    async fn lazy_models_spawn_their_coroutine_on_the_first_send() {
+        let (mut dom, model) = mount_model(use_tea_model_lazy::<Counter>);
+        run(&mut dom).await;
+        assert!(
+            dom.in_runtime(|| model.idle.peek().is_some()),
+            "Nothing is spawned until an action arrives"
+        );
+        assert_eq!(dom.in_runtime(|| model.peek().count), 0);
+
+        dom.in_runtime(|| {
+            model.send(CounterAction::Increment);
+            model.send(CounterAction::Double);
+        });
+        run(&mut dom).await;
+        assert!(dom.in_runtime(|| model.idle.peek().is_none()));
+        assert_eq!(dom.in_runtime(|| model.peek().count), 2);
+    }
+
+    #[tokio::test]
+    async fn sends_after_shutdown_are_ignored() {
+        let (mut dom, model) = mount_model(use_tea_model::<Counter>);
+        dom.in_runtime(|| {
+            model.send(CounterAction::Increment);
+            model.shutdown();
+            model.send(CounterAction::Increment);
+            model.send_batch([CounterAction::Double, CounterAction::Double]);
+        });
+        run(&mut dom).await;
+
+        assert_eq!(
+            dom.in_runtime(|| (model.peek().count, model.pending_len())),
+            (1, 0),
+            "The action queued before the shutdown is still applied"
+        );
+    }
+
+    #[test]
+    fn the_coroutine_completes_once_its_senders_are_dropped() {
+        let (dom, model) = mount_model(use_tea_model::<Counter>);
+        let (tx, rx) = mpsc::unbounded();
+        tx.unbounded_send(Envelope::Action(CounterAction::Increment)).unwrap();
+        tx.unbounded_send(Envelope::Batch(vec![CounterAction::Double])).unwrap();
+        drop(tx);
+
+        // the future would never complete if the loop kept polling the ended stream
+        dom.in_runtime(|| futures_executor::block_on(work_off(&model, ScopeId::APP, rx)));
+        assert_eq!(
+            dom.in_runtime(|| model.peek().count),
+            2,
+            "The messages sent before are applied as usual"
+        );
+    }
+
+    #[tokio::test]
+    async fn actions_are_moved_into_update() {
+        /// A large payload, which must never be cloned on its way to `update`.
+        struct Photo(Vec<u8>);
+
+        impl Clone for Photo {
+            fn clone(&self) -> Self {
+                panic!("the photo was cloned");
+            }
+        }
+
+        #[derive(Default, Clone, PartialEq, Debug)]
+        struct Order {
+            photo_at: Option<usize>,
+        }
+
+        impl TeaModel for Order {
+            type Action = Photo;
+
+            fn update(&mut self, photo: Self::Action) -> Cmd<Self::Action> {
+                self.photo_at = Some(photo.0.as_ptr() as usize);
+                Cmd::none()
+            }
+        }
+
+        let (mut dom, order) = mount_model(use_tea_model::<Order>);
+        let photo = Photo(vec![0; 4 * 1024 * 1024]);
+        let sent_at = photo.0.as_ptr() as usize;
+        dom.in_runtime(|| order.send(photo));
+        run(&mut dom).await;
+
+        assert_eq!(
+            dom.in_runtime(|| order.peek().photo_at),
+            Some(sent_at),
+            "The payload reaches update without being copied"
+        );
+    }
+
+    #[test]
+    fn reset_bypasses_update() {
+        let (dom, kettle) = mount_model(use_tea_model::<Kettle>);
+        dom.in_runtime(|| {
+            kettle.reset_with(Kettle { temperature: 120 });
+            assert_eq!(kettle.peek().temperature, 120, "The temperature is not validated");
+            assert_eq!(kettle.errors(), None);
+
+            kettle.reset();
+            assert_eq!(*kettle.peek(), Kettle::default());
+        });
+    }
+
+    #[test]
+    fn try_read_returns_none_while_the_state_is_written() {
+        let (dom, kettle) = mount_model(use_tea_model::<Kettle>);
+        dom.in_runtime(|| {
+            kettle.with_mut(|_| {
+                assert!(kettle.try_read().is_none());
+                assert!(kettle.try_peek().is_none());
+            });
+            assert_eq!(kettle.try_peek().as_deref(), Some(&Kettle::default()));
+        });
+    }
+
+    #[test]
+    fn last_changed_tells_whether_the_action_changed_the_state() {
+        let (dom, model) = mount_model(use_tea_model::<Counter>);
+        dom.in_runtime(|| {
+            // doubling zero leaves the count unchanged
+            model.send_sync(CounterAction::Double);
+            assert!(!model.last_changed());
+            model.send_sync(CounterAction::Increment);
+            assert!(model.last_changed());
+        });
+    }
+
+    #[test]
+    fn version_counts_the_writes_of_the_state() {
+        let (dom, model) = mount_model(use_tea_model::<Counter>);
+        dom.in_runtime(|| {
+            assert_eq!(model.version(), 0);
+            model.send_sync(CounterAction::Double);
+            assert_eq!(model.version(), 1, "The unchanged state is written anyway");
+            model.reset();
+            assert_eq!(model.version(), 2, "A reset does not start over");
+        });
+
+        let (dom, model) = mount_model(use_tea_model_dedup::<Counter>);
+        dom.in_runtime(|| {
+            model.send_sync(CounterAction::Double);
+            assert_eq!(model.version(), 0);
+            model.send_sync(CounterAction::Increment);
+            assert_eq!(model.version(), 1);
+        });
+    }
+
+    #[test]
+    fn with_mut_bypasses_update() {
+        let (dom, kettle) = mount_model(use_tea_model::<Kettle>);
+        dom.in_runtime(|| {
+            kettle.with_mut(|kettle| kettle.temperature += 130);
+            assert_eq!(kettle.peek().temperature, 130);
+            assert_eq!(kettle.errors(), None, "The temperature is not validated");
+        });
+    }
+
+    #[tokio::test]
+    async fn batches_keep_their_place_in_the_queue() {
+        thread_local! {
+            static RENDERS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+        }
+
+        fn app() -> Element {
+            let model = use_tea_model::<Counter>();
+            use_context_provider(|| model);
+            RENDERS.set(RENDERS.get() + 1);
+            let count = model.read().count;
+            rsx! { "{count}" }
+        }
+
+        let (mut dom, model) = mount::<TeaModelSignal<Counter>>(app);
+        dom.in_runtime(|| {
+            model.send(CounterAction::Increment);
+            model.send_batch([CounterAction::Double, CounterAction::Increment]);
+            model.send(CounterAction::Double);
+        });
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| model.peek().count), 6);
+        assert_eq!(RENDERS.get(), 2, "All queued actions are rendered at once");
+    }
+
+    #[tokio::test]
+    async fn dedup_models_skip_renders_for_unchanged_states() {
+        thread_local! {
+            static RENDERS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+        }
+
+        fn app() -> Element {
+            let model = use_tea_model_dedup::<Counter>();
+            use_context_provider(|| model);
+            RENDERS.set(RENDERS.get() + 1);
+            let count = model.read().count;
+            rsx! { "{count}" }
+        }
+
+        let (mut dom, model) = mount::<TeaModelSignal<Counter>>(app);
+        // doubling zero leaves the count unchanged
+        dom.in_runtime(|| model.send(CounterAction::Double));
+        run(&mut dom).await;
+        assert_eq!(RENDERS.get(), 1, "A no-op action does not re-render");
+
+        dom.in_runtime(|| model.send(CounterAction::Increment));
+        run(&mut dom).await;
+        assert_eq!(RENDERS.get(), 2);
+        assert_eq!(dom.in_runtime(|| model.peek().count), 1);
+    }
+
+    #[tokio::test]
+    async fn unchanged_reports_skip_renders() {
+        #[derive(Default, Clone, PartialEq, Debug)]
+        struct Cup {
+            tea_bags: u8,
+        }
+
+        impl TeaModel for Cup {
+            type Action = ();
+
+            fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+                self.update_checked(action).0
+            }
+
+            fn update_checked(&mut self, (): Self::Action) -> (Cmd<Self::Action>, Changed) {
+                // a cup only takes a single tea bag
+                if self.tea_bags > 0 {
+                    return (Cmd::none(), Changed::No);
+                }
+                self.tea_bags += 1;
+                (Cmd::none(), Changed::Yes)
+            }
+        }
+
+        thread_local! {
+            static RENDERS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+        }
+
+        fn app() -> Element {
+            let model = use_tea_model::<Cup>();
+            use_context_provider(|| model);
+            RENDERS.set(RENDERS.get() + 1);
+            let tea_bags = model.read().tea_bags;
+            rsx! { "{tea_bags}" }
+        }
+
+        let (mut dom, cup) = mount::<TeaModelSignal<Cup>>(app);
+        dom.in_runtime(|| cup.send(()));
+        run(&mut dom).await;
+        assert_eq!(RENDERS.get(), 2);
+
+        dom.in_runtime(|| cup.send(()));
+        run(&mut dom).await;
+        assert_eq!(RENDERS.get(), 2, "The rejected tea bag does not re-render");
+        dom.in_runtime(|| {
+            assert_eq!(cup.peek().tea_bags, 1);
+            assert!(!cup.last_changed());
+        });
+    }
+
+    #[tokio::test]
+    async fn custom_change_detection_compares_revisions() {
+        #[derive(Default, Clone, PartialEq, Debug)]
+        struct Catalogue {
+            revision: u64,
+            teas: Vec<String>,
+            // bookkeeping that readers do not care about, so it is changed without a new revision
+            lookups: u32,
+        }
+
+        impl TeaModel for Catalogue {
+            type Action = Option<String>;
+
+            fn update(&mut self, tea: Self::Action) -> Cmd<Self::Action> {
+                match tea {
+                    Some(tea) => {
+                        self.teas.push(tea);
+                        self.revision += 1;
+                    }
+                    None => self.lookups += 1,
+                }
+                Cmd::none()
+            }
+
+            fn changed(old: &Self, new: &Self) -> bool {
+                old.revision != new.revision
+            }
+        }
+
+        let (mut dom, catalogue) = mount_model(use_tea_model_dedup::<Catalogue>);
+        dom.in_runtime(|| catalogue.send(None));
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| (catalogue.last_changed(), catalogue.peek().lookups)),
+            (false, 0),
+            "The lookup does not count as a change, so the dedup model drops it"
+        );
+
+        dom.in_runtime(|| catalogue.send(Some("sencha".to_string())));
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| (catalogue.last_changed(), catalogue.peek().revision)),
+            (true, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn initial_action_is_dispatched_once() {
+        fn app() -> Element {
+            let model = use_tea_model_init::<Counter>(CounterAction::Increment);
+            use_context_provider(|| model);
+            if generation() < 5 {
+                // re-render a few times
+                needs_update();
+            }
+            rsx! {}
+        }
+
+        let (mut dom, model) = mount::<TeaModelSignal<Counter>>(app);
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| model.peek().count), 1);
+    }
+
+    #[tokio::test]
+    async fn queries_see_the_state_right_after_their_action() {
+        let (mut dom, model) = mount_model(use_tea_model::<Counter>);
+        let answer = Rc::new(std::cell::Cell::new(None));
+        dom.in_scope(ScopeId::APP, || {
+            let answer = answer.clone();
+            let count = model.send_query(CounterAction::Increment, |counter| counter.count);
+            model.send(CounterAction::Double);
+            spawn(async move { answer.set(Some(count.await)) });
+        });
+        run(&mut dom).await;
+
+        assert_eq!(
+            answer.get(),
+            Some(1),
+            "The query is answered before the next action is applied"
+        );
+        assert_eq!(dom.in_runtime(|| model.peek().count), 2);
+    }
+
+    #[tokio::test]
+    async fn queued_actions_are_counted_until_applied() {
+        let (mut dom, model) = mount_model(use_tea_model::<Counter>);
+        dom.in_runtime(|| {
+            assert!(model.is_idle());
+            model.send(CounterAction::Increment);
+            model.send_batch([CounterAction::Increment, CounterAction::Double]);
+            assert_eq!(model.pending_len(), 3);
+        });
+        run(&mut dom).await;
+
+        dom.in_runtime(|| {
+            assert_eq!(model.peek().count, 4);
+            assert!(model.is_idle());
+        });
+    }
+
+    #[tokio::test]
+    async fn debug_output_shows_the_state_and_the_queue() {
+        let (mut dom, model) = mount_model(use_tea_model::<Counter>);
+        dom.in_runtime(|| {
+            model.send(CounterAction::Increment);
+            assert_eq!(
+                format!("{model:?}"),
+                "TeaModelSignal { state: Counter { count: 0 }, pending: 1, .. }"
+            );
+        });
+        run(&mut dom).await;
+        assert!(dom.in_runtime(|| format!("{model:?}")).contains("count: 1"));
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Heater {
+        temperature: u8,
+        updates: usize,
+    }
+
+    impl TeaModel for Heater {
+        type Action = u8;
+
+        fn update(&mut self, temperature: Self::Action) -> Cmd<Self::Action> {
+            self.temperature = temperature;
+            self.updates += 1;
+            Cmd::none()
+        }
+
+        fn coalesce(pending: &mut VecDeque<Self::Action>) {
+            Self::dedup_adjacent(pending);
+        }
+    }
+
+    #[tokio::test]
+    async fn identical_queued_actions_are_coalesced() {
+        let (mut dom, heater) = mount_model(use_tea_model::<Heater>);
+        dom.in_runtime(|| {
+            for _ in 0..3 {
+                heater.send(80);
+            }
+        });
+        run(&mut dom).await;
+
+        dom.in_runtime(|| {
+            assert_eq!(heater.peek().temperature, 80);
+            assert_eq!(heater.peek().updates, 1, "The three sends collapse into one update");
+            assert!(heater.is_idle());
+        });
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Log {
+        applied: Vec<u32>,
+    }
+
+    /// Produces this action once the action before it has been applied.
+    const FOLLOW_UP: u32 = u32::MAX;
+
+    impl TeaModel for Log {
+        type Action = u32;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            self.applied.push(action);
+            if action == 0 {
+                return Cmd::perform(std::future::ready(()), |()| FOLLOW_UP);
+            }
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn interleaved_sends_are_applied_in_fifo_order() {
+        let (mut dom, log) = mount_model(use_tea_model::<Log>);
+        let next = Rc::new(std::cell::Cell::new(1));
+        dom.in_scope(ScopeId::APP, || {
+            // two senders, taking turns
+            for _ in 0..2 {
+                let next = next.clone();
+                spawn(async move {
+                    for _ in 0..50 {
+                        log.send(next.get());
+                        next.set(next.get() + 1);
+                        tokio::task::yield_now().await;
+                    }
                 });
             }
+        });
+        run(&mut dom).await;
+
+        let applied = dom.in_runtime(|| log.peek().applied.clone());
+        assert_eq!(applied, (1..=100).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn actions_of_commands_queue_up_behind_earlier_sends() {
+        let (mut dom, log) = mount_model(use_tea_model::<Log>);
+        dom.in_scope(ScopeId::APP, || {
+            spawn(async move {
+                log.dispatch(0).await;
+                // the command of 0 has completed by now, so its follow-up is already queued
+                tokio::task::yield_now().await;
+                log.send(1);
+            });
+        });
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| log.peek().applied.clone()), [0, FOLLOW_UP, 1]);
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Teacup {
+        steps: Vec<TeacupAction>,
+    }
+
+    #[derive(Clone, PartialEq, Debug)]
+    enum TeacupAction {
+        AddWater(u8),
+        Done,
+    }
+
+    impl TeaModel for Teacup {
+        type Action = TeacupAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            let hot_enough = matches!(action, TeacupAction::AddWater(temperature) if temperature >= 70);
+            self.steps.push(action);
+            if hot_enough {
+                return Cmd::dispatch_next(TeacupAction::Done);
+            }
+            Cmd::none()
         }
-    });
+    }
 
-    TeaModelSignal { inner, co }
+    #[tokio::test]
+    async fn follow_ups_are_applied_before_the_queued_actions() {
+        let (mut dom, teacup) = mount_model(use_tea_model::<Teacup>);
+        dom.in_runtime(|| {
+            teacup.send(TeacupAction::AddWater(90));
+            teacup.send(TeacupAction::AddWater(60));
+        });
+        run(&mut dom).await;
+
+        assert_eq!(
+            dom.in_runtime(|| teacup.peek().steps.clone()),
+            [
+                TeacupAction::AddWater(90),
+                TeacupAction::Done,
+                TeacupAction::AddWater(60)
+            ]
+        );
+    }
+
+    #[test]
+    fn processing_an_action_applies_its_follow_ups() {
+        let mut teacup = Teacup::default();
+        let cmd = process_action(&mut teacup, TeacupAction::AddWater(90));
+        assert!(cmd.is_none(), "The follow-up has been applied already");
+        assert_eq!(teacup.steps, [TeacupAction::AddWater(90), TeacupAction::Done]);
+
+        assert!(process_action(&mut teacup, TeacupAction::AddWater(60)).is_none());
+        assert_eq!(teacup.steps.len(), 3, "Cold water has no follow-up");
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Brew {
+        done: bool,
+    }
+
+    enum BrewAction {
+        Brew,
+        Done,
+        CupFetched,
+    }
+
+    impl TeaModel for Brew {
+        type Action = BrewAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                BrewAction::Brew => {
+                    return Cmd::perform(tokio::time::sleep(Duration::from_millis(10)), |()| BrewAction::Done);
+                }
+                BrewAction::Done => self.done = true,
+                BrewAction::CupFetched => self.done = false,
+            }
+            Cmd::none()
+        }
+
+        fn cancels_effects(action: &Self::Action) -> bool {
+            matches!(action, BrewAction::CupFetched)
+        }
+    }
+
+    #[tokio::test]
+    async fn resetting_cancels_running_commands() {
+        let (mut dom, brew) = mount_model(use_tea_model::<Brew>);
+        dom.in_runtime(|| brew.send(BrewAction::Brew));
+        run(&mut dom).await;
+        assert!(dom.in_runtime(|| brew.peek().done), "Brewing finishes when left alone");
+
+        dom.in_runtime(|| {
+            brew.send(BrewAction::CupFetched);
+            brew.send(BrewAction::Brew);
+            brew.send(BrewAction::CupFetched);
+        });
+        run(&mut dom).await;
+        assert!(
+            !dom.in_runtime(|| brew.peek().done),
+            "The slow Done is cancelled by CupFetched"
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_reducers_dispatch_to_their_handlers() {
+        #[derive(Default, Clone, PartialEq, Debug)]
+        struct Tray {
+            cups: Vec<String>,
+        }
+
+        #[derive(TeaReducer)]
+        #[tea(state = Tray)]
+        enum TrayAction {
+            Clear,
+            AddCup(String),
+            Refill { times: u8 },
+        }
+
+        impl Tray {
+            fn handle_clear(&mut self) {
+                self.cups.clear();
+            }
+
+            fn handle_add_cup(&mut self, cup: String) {
+                self.cups.push(cup);
+            }
+
+            fn handle_refill(&mut self, times: u8) -> Cmd<TrayAction> {
+                let cups = self.cups.len();
+                Cmd::perform(std::future::ready(()), move |()| {
+                    TrayAction::AddCup(format!("refill {times} of {cups}"))
+                })
+            }
+        }
+
+        let mut tray = Tray::default();
+        assert!(tray.update(TrayAction::AddCup("mug".into())).is_none());
+        assert!(!tray.update(TrayAction::Refill { times: 2 }).is_none());
+        assert_eq!(tray.cups, ["mug"]);
+        assert!(tray.update(TrayAction::Clear).is_none());
+        assert!(tray.cups.is_empty());
+    }
+
+    #[cfg(feature = "derive")]
+    #[tokio::test]
+    async fn derived_actions_send_their_variants() {
+        #[derive(TeaActions)]
+        enum CupAction {
+            Rinse,
+            Fill(u8, bool),
+            Stir { times: u8 },
+        }
+
+        #[derive(Default, Clone, PartialEq, Debug)]
+        struct Cup {
+            steps: Vec<String>,
+        }
+
+        impl TeaModel for Cup {
+            type Action = CupAction;
+
+            fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+                self.steps.push(match action {
+                    CupAction::Rinse => "rinse".to_string(),
+                    CupAction::Fill(millilitres, hot) => format!("fill {millilitres} {hot}"),
+                    CupAction::Stir { times } => format!("stir {times}"),
+                });
+                Cmd::none()
+            }
+        }
+
+        let (mut dom, cup) = mount_model(use_tea_model::<Cup>);
+        dom.in_runtime(|| {
+            cup.rinse();
+            cup.fill(200, true);
+            cup.stir(3);
+        });
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| cup.peek().steps.clone()),
+            ["rinse", "fill 200 true", "stir 3"]
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[tokio::test]
+    async fn derived_lenses_only_follow_their_field() {
+        #[derive(Default, Clone, PartialEq, Debug, TeaLenses)]
+        struct Brew {
+            status: &'static str,
+            temperature: u8,
+        }
+
+        enum BrewAction {
+            Status(&'static str),
+            Heat(u8),
+        }
+
+        impl TeaModel for Brew {
+            type Action = BrewAction;
+
+            fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+                match action {
+                    BrewAction::Status(status) => self.status = status,
+                    BrewAction::Heat(temperature) => self.temperature = temperature,
+                }
+                Cmd::none()
+            }
+        }
+
+        thread_local! {
+            static STATUS_RENDERS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+        }
+
+        #[component]
+        fn StatusLine(brew: TeaModelSignal<Brew>) -> Element {
+            let status = use_hook(|| brew.status_signal());
+            STATUS_RENDERS.set(STATUS_RENDERS.get() + 1);
+            rsx! { "{status}" }
+        }
+
+        fn app() -> Element {
+            let brew = use_tea_model::<Brew>();
+            use_context_provider(|| brew);
+            rsx! { StatusLine { brew } }
+        }
+
+        let (mut dom, brew) = mount::<TeaModelSignal<Brew>>(app);
+        dom.in_runtime(|| brew.send(BrewAction::Heat(90)));
+        run(&mut dom).await;
+        assert_eq!(STATUS_RENDERS.get(), 1, "Heating leaves the status alone");
+
+        dom.in_runtime(|| brew.send(BrewAction::Status("steeping")));
+        run(&mut dom).await;
+        assert_eq!(STATUS_RENDERS.get(), 2);
+    }
 }
@@ -0,0 +1,82 @@
+//! Timing of the updates of TEA models, enabled with the `metrics` feature.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::prelude::ReadableExt;
+use std::time::Duration;
+
+/// Statistics on how long the `update` calls of a model took, see [`TeaModelSignal::metrics`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct TeaMetrics {
+    /// Number of actions applied.
+    pub count: u64,
+    /// Time spent in `update`, summed over all actions.
+    pub total_time: Duration,
+    /// The longest time a single action took.
+    pub max_time: Duration,
+    /// The time the most recently applied action took.
+    pub last_time: Duration,
+}
+
+impl TeaMetrics {
+    /// Records an action that took `elapsed`.
+    pub(crate) fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total_time += elapsed;
+        self.max_time = self.max_time.max(elapsed);
+        self.last_time = elapsed;
+    }
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    #[must_use]
+    /// Returns the timing statistics of every action this model applied so far.
+    ///
+    /// Every action is timed, including the ones of batches and the ones produced by commands; the times cover the
+    /// processor of the model, so the middleware and history of a model count as well, but not its commands. That
+    /// points out expensive actions once the reducer grows: a `max_time` far above the average of `total_time` over
+    /// `count` is worth a closer look, e.g. with the `tracing` feature. Reading the metrics does not subscribe the
+    /// current component.
+    ///
+    /// Taking two timestamps per action adds a small overhead, which is why the metrics are behind a feature that is
+    /// off by default.
+    pub fn metrics(&self) -> TeaMetrics {
+        *self.metrics.peek()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Pot {
+        cups: u8,
+    }
+
+    impl TeaModel for Pot {
+        type Action = u8;
+
+        fn update(&mut self, cups: Self::Action) -> Cmd<Self::Action> {
+            self.cups += cups;
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn every_action_is_counted() {
+        let (mut dom, pot) = mount_model(use_tea_model::<Pot>);
+        dom.in_runtime(|| {
+            pot.send(1);
+            pot.send_batch([1, 1]);
+        });
+        run(&mut dom).await;
+
+        let metrics = dom.in_runtime(|| pot.metrics());
+        assert_eq!(metrics.count, 3, "The actions of a batch are counted one by one");
+        assert!(metrics.max_time <= metrics.total_time);
+    }
+}
@@ -0,0 +1,190 @@
+//! Middleware that runs around every update of a TEA model.
+
+use crate::{apply, use_tea_model_processed, Cmd, TeaModel, TeaModelInit, TeaModelSignal};
+
+/// Whether an action should continue on to the next middleware and, eventually, to `update`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flow {
+    /// Pass the action on.
+    Continue,
+    /// Drop the action; neither `update` nor any of the remaining middleware will see it.
+    Halt,
+}
+
+/// Middleware that can observe, validate or reject actions of a model, e.g. for logging or to refuse actions while
+/// offline.
+pub trait TeaMiddleware<T: TeaModel> {
+    /// Runs before the action is passed to `update`, with the state as it is before the update.
+    ///
+    /// Returning [`Flow::Halt`] drops the action.
+    fn before(&self, _action: &T::Action, _state: &T) -> Flow {
+        Flow::Continue
+    }
+
+    /// Runs after the action has been applied, with the updated state.
+    ///
+    /// Only runs when [`before`](Self::before) let the action continue.
+    fn after(&self, _action: &T::Action, _state: &T) {}
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, running every action through `middleware`.
+///
+/// The `before` hooks run in registration order and the `after` hooks in reverse order, like the layers of an
+/// onion. When a middleware halts an action, the outer layers that already saw it still get their `after` call, and
+/// the state is left unchanged.
+///
+/// Since `after` needs the action once `update` has consumed it, the action type must be `Clone`.
+pub fn use_tea_model_with_middleware<T>(middleware: Vec<Box<dyn TeaMiddleware<T>>>) -> TeaModelSignal<T>
+where
+    T: TeaModel + TeaModelInit,
+    T::Action: Clone,
+{
+    use_tea_model_processed(T::init, move || {
+        Box::new(move |state: &mut T, error, action| {
+            let passed = middleware
+                .iter()
+                .take_while(|layer| layer.before(&action, state) == Flow::Continue)
+                .count();
+
+            let cmd = if passed == middleware.len() {
+                apply(state, error, action.clone())
+            } else {
+                Cmd::none()
+            };
+
+            for layer in middleware[..passed].iter().rev() {
+                layer.after(&action, state);
+            }
+            cmd
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus::prelude::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Default, Clone, PartialEq)]
+    struct Cup {
+        sugar: u8,
+    }
+
+    impl TeaModel for Cup {
+        type Action = u8;
+
+        fn update(&mut self, sugar: Self::Action) -> Cmd<Self::Action> {
+            self.sugar += sugar;
+            Cmd::none()
+        }
+    }
+
+    type Calls = Rc<RefCell<Vec<String>>>;
+
+    struct Log(&'static str, Calls);
+
+    impl TeaMiddleware<Cup> for Log {
+        fn before(&self, _action: &u8, state: &Cup) -> Flow {
+            self.1.borrow_mut().push(format!("{} before {}", self.0, state.sugar));
+            Flow::Continue
+        }
+
+        fn after(&self, _action: &u8, state: &Cup) {
+            self.1.borrow_mut().push(format!("{} after {}", self.0, state.sugar));
+        }
+    }
+
+    /// Rejects adding more than two sugars at once.
+    struct TooSweet;
+
+    impl TeaMiddleware<Cup> for TooSweet {
+        fn before(&self, action: &u8, _state: &Cup) -> Flow {
+            if *action > 2 {
+                Flow::Halt
+            } else {
+                Flow::Continue
+            }
+        }
+    }
+
+    fn run_with(middleware: fn(Calls) -> Vec<Box<dyn TeaMiddleware<Cup>>>, actions: &[u8]) -> (Cup, Vec<String>) {
+        #[derive(Clone, Props)]
+        struct AppProps {
+            middleware: fn(Calls) -> Vec<Box<dyn TeaMiddleware<Cup>>>,
+            calls: Calls,
+        }
+
+        impl PartialEq for AppProps {
+            fn eq(&self, _other: &Self) -> bool {
+                true
+            }
+        }
+
+        #[allow(clippy::needless_pass_by_value)]
+        fn app(props: AppProps) -> Element {
+            let model = use_tea_model_with_middleware((props.middleware)(props.calls.clone()));
+            use_context_provider(|| model);
+            rsx! {}
+        }
+
+        let calls = Calls::default();
+        let mut dom = VirtualDom::new_with_props(
+            app,
+            AppProps {
+                middleware,
+                calls: calls.clone(),
+            },
+        );
+        dom.rebuild_in_place();
+
+        let model = dom.in_scope(ScopeId::APP, consume_context::<TeaModelSignal<Cup>>);
+        let cup = dom.in_runtime(|| {
+            for sugar in actions {
+                model.send_sync(*sugar);
+            }
+            model.peek().clone()
+        });
+        (cup, calls.take())
+    }
+
+    #[test]
+    fn middleware_runs_like_an_onion() {
+        let (cup, calls) = run_with(
+            |calls| vec![Box::new(Log("outer", calls.clone())), Box::new(Log("inner", calls))],
+            &[1],
+        );
+        assert_eq!(cup.sugar, 1);
+        assert_eq!(
+            calls,
+            ["outer before 0", "inner before 0", "inner after 1", "outer after 1"]
+        );
+    }
+
+    #[test]
+    fn halted_actions_do_not_reach_update() {
+        let (cup, calls) = run_with(
+            |calls| {
+                vec![
+                    Box::new(Log("outer", calls.clone())),
+                    Box::new(TooSweet),
+                    Box::new(Log("inner", calls)),
+                ]
+            },
+            &[2, 3],
+        );
+        assert_eq!(cup.sugar, 2, "Adding three sugars is rejected");
+        assert_eq!(
+            calls,
+            [
+                "outer before 0",
+                "inner before 0",
+                "inner after 2",
+                "outer after 2",
+                "outer before 2",
+                "outer after 2"
+            ]
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Transient notifications emitted by reducers, for toast-style feedback that does not belong in the state.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::prelude::{spawn, use_hook, Signal, WritableExt};
+use futures_channel::mpsc;
+use futures_util::StreamExt;
+use std::{cell::RefCell, collections::VecDeque, time::Duration};
+
+thread_local! {
+    /// The messages emitted by the update that is currently being applied, if any.
+    static EMITTED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// A notification emitted with [`TeaNotifier::notify`], as listed by [`use_tea_notifications`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Notification {
+    /// Identifies the notification among the ones of the same hook, e.g. as the key of a list.
+    pub id: u64,
+    /// The message passed to [`TeaNotifier::notify`].
+    pub message: String,
+}
+
+/// The channel through which `update` emits notifications.
+///
+/// Notifications are ephemeral feedback for the user, like "Tea bag added!", which should not end up in the state,
+/// where they would have to be cleared by yet another action, and would be persisted, recorded and undone along
+/// with everything else.
+pub struct TeaNotifier;
+
+impl TeaNotifier {
+    /// Emits a notification from within `update`.
+    ///
+    /// The model's coroutine collects the notifications of every action it applies, and passes them on to the
+    /// components that called [`use_tea_notifications`] once the action has been applied. Outside of the coroutine,
+    /// e.g. when a unit test calls `update` directly, or for the models whose updates run on another thread, the
+    /// notification is dropped. So are the ones of an action whose update panicked.
+    pub fn notify(message: impl Into<String>) {
+        EMITTED.with_borrow_mut(|emitted| {
+            if let Some(emitted) = emitted {
+                emitted.push(message.into());
+            }
+        });
+    }
+}
+
+/// Runs `f`, returning its result along with the notifications it emitted.
+pub(crate) fn collect<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    // an update that applies another model's action right away keeps its own notifications apart
+    let outer = EMITTED.replace(Some(Vec::new()));
+    let result = f();
+    let emitted = EMITTED.replace(outer).unwrap_or_default();
+    (result, emitted)
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Passes the notifications emitted by an update on to every listener, dropping the listeners that are gone.
+    pub(crate) fn notify(&self, emitted: &[String]) {
+        if emitted.is_empty() {
+            return;
+        }
+        let mut notifications = self.notifications;
+        notifications.write().retain(|listener| {
+            emitted
+                .iter()
+                .all(|message| listener.unbounded_send(message.clone()).is_ok())
+        });
+    }
+}
+
+#[must_use]
+/// Returns the notifications that the updates of `model` emitted with [`TeaNotifier::notify`] during the last
+/// `expire_after`, oldest first.
+///
+/// Every notification is removed again once it expired, so rendering the list is all it takes for toasts. The
+/// signal is owned by the current component; write to it to dismiss a notification early. Only the notifications
+/// emitted after the first render are listed, and `expire_after` is only used on the first render. The
+/// notifications expire on the model's [clock](TeaModelSignal::set_clock), so tests can expire them with a manual one.
+pub fn use_tea_notifications<T: TeaModel>(
+    model: TeaModelSignal<T>,
+    expire_after: Duration,
+) -> Signal<VecDeque<Notification>> {
+    use_hook(|| {
+        let mut notifications = Signal::new(VecDeque::new());
+        let (listener, mut emitted) = mpsc::unbounded();
+        let mut listeners = model.notifications;
+        listeners.write().push(listener);
+
+        spawn(async move {
+            let mut next_id = 0;
+            while let Some(message) = emitted.next().await {
+                let id = next_id;
+                next_id += 1;
+                notifications.write().push_back(Notification { id, message });
+                let expired = model.clock().sleep(expire_after);
+                spawn(async move {
+                    expired.await;
+                    notifications.write().retain(|notification| notification.id != id);
+                });
+            }
+        });
+        notifications
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount, run, run_for},
+        use_tea_model, Cmd, TeaClock,
+    };
+    use dioxus::prelude::*;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Cup {
+        tea_bags: u8,
+    }
+
+    impl TeaModel for Cup {
+        type Action = u8;
+
+        fn update(&mut self, tea_bags: Self::Action) -> Cmd<Self::Action> {
+            self.tea_bags += tea_bags;
+            TeaNotifier::notify(format!("{tea_bags} tea bags added!"));
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn notifications_expire_after_a_while() {
+        fn app() -> Element {
+            let model = use_tea_model::<Cup>();
+            let notifications = use_tea_notifications(model, Duration::from_millis(30));
+            use_context_provider(|| (model, notifications));
+            rsx! {}
+        }
+
+        let (mut dom, (cup, notifications)) = mount::<(TeaModelSignal<Cup>, Signal<VecDeque<Notification>>)>(app);
+        dom.in_runtime(|| cup.send(1));
+        run_for(&mut dom, Duration::from_millis(20)).await;
+        dom.in_runtime(|| cup.send(2));
+        run_for(&mut dom, Duration::from_millis(20)).await;
+
+        let messages = |dom: &VirtualDom| {
+            dom.in_runtime(|| {
+                notifications
+                    .peek()
+                    .iter()
+                    .map(|notification| notification.message.clone())
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(messages(&dom), ["2 tea bags added!"], "The first notification expired");
+
+        run_for(&mut dom, Duration::from_millis(20)).await;
+        assert!(messages(&dom).is_empty());
+        assert_eq!(
+            dom.in_runtime(|| cup.peek().tea_bags),
+            3,
+            "Notifying leaves the state alone"
+        );
+    }
+
+    #[tokio::test]
+    async fn notifications_expire_on_the_clock_of_the_model() {
+        fn app() -> Element {
+            let model = use_tea_model::<Cup>();
+            let notifications = use_tea_notifications(model, Duration::from_secs(5));
+            use_context_provider(|| (model, notifications));
+            rsx! {}
+        }
+
+        let (mut dom, (cup, notifications)) = mount::<(TeaModelSignal<Cup>, Signal<VecDeque<Notification>>)>(app);
+        let clock = TeaClock::manual();
+        dom.in_runtime(|| {
+            cup.set_clock(clock.clone());
+            cup.send(1);
+        });
+        run(&mut dom).await;
+        assert_eq!(dom.in_runtime(|| notifications.peek().len()), 1);
+
+        clock.advance(Duration::from_secs(5));
+        run(&mut dom).await;
+        assert!(dom.in_runtime(|| notifications.peek().is_empty()));
+    }
+
+    #[test]
+    fn notifications_are_collected_per_update() {
+        let mut cup = Cup::default();
+        let ((), emitted) = collect(|| {
+            let _ = cup.update(1);
+        });
+        assert_eq!(emitted, ["1 tea bags added!"]);
+
+        let _ = cup.update(1);
+        assert_eq!(
+            collect(|| ()).1,
+            Vec::<String>::new(),
+            "Nothing lingers between updates"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Optimistic updates, which are rolled back when the work backing them fails.
+
+use crate::{TeaModel, TeaModelSignal};
+use std::future::Future;
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Applies `action` right away, and restores the state from before it if `future` fails.
+    ///
+    /// This is meant for changes backed by a request that usually succeeds, like placing a tea order: the order
+    /// shows up immediately, and disappears again if the server refuses it. The action is applied with
+    /// [`send_sync`](Self::send_sync), so the snapshot taken just before it is exactly the state it was applied to.
+    /// On success, nothing further happens. On failure, the snapshot is written back directly, bypassing `update`
+    /// like [`with_mut`](Self::with_mut).
+    ///
+    /// Other actions may be applied while `future` is pending. The snapshot wins: a rollback also discards whatever
+    /// those actions changed, so that the state never contains the effects of a failed action. Keep the actions that
+    /// are allowed while an optimistic update is in flight to the ones that are safe to lose, or re-send them after
+    /// a failure.
+    ///
+    /// The future is spawned in the scope of the model's component and is dropped, without rolling back, when that
+    /// component unmounts.
+    pub fn send_optimistic<E: 'static>(
+        &self,
+        action: T::Action,
+        future: impl Future<Output = Result<(), E>> + 'static,
+    ) {
+        let snapshot = self.peek().clone();
+        self.send_sync(action);
+
+        let model = *self;
+        self.spawn(async move {
+            if future.await.is_err() {
+                model.with_mut(|state| *state = snapshot);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+    use dioxus::prelude::*;
+    use std::future::ready;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    enum Status {
+        #[default]
+        EmptyCup,
+        Ordered,
+    }
+
+    impl TeaModel for Status {
+        type Action = ();
+
+        fn update(&mut self, (): Self::Action) -> Cmd<Self::Action> {
+            *self = Status::Ordered;
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn failures_restore_the_snapshot() {
+        let (mut dom, status) = mount_model(use_tea_model::<Status>);
+        dom.in_scope(ScopeId::APP, || {
+            status.send_optimistic((), ready(Err("out of tea")));
+            assert_eq!(*status.peek(), Status::Ordered, "The action is applied right away");
+        });
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| status.peek().clone()), Status::EmptyCup);
+    }
+
+    #[tokio::test]
+    async fn successes_keep_the_optimistic_state() {
+        let (mut dom, status) = mount_model(use_tea_model::<Status>);
+        dom.in_scope(ScopeId::APP, || status.send_optimistic((), ready(Ok::<_, ()>(()))));
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| status.peek().clone()), Status::Ordered);
+    }
+}
@@ -0,0 +1,84 @@
+//! Isolating panics of `update`, so a single bad action does not take down the whole model.
+
+use std::any::Any;
+
+/// What the coroutine does after `update` panicked, as decided by [`TeaModel::on_panic`](crate::TeaModel::on_panic).
+#[derive(Clone, PartialEq, Debug)]
+pub enum TeaPanicPolicy<T> {
+    /// Drops the action and keeps the state from before it, then carries on with the next action.
+    Skip,
+    /// Replaces the state with the given one, e.g. the initial state, and cancels the running commands, which
+    /// belong to the state that is thrown away.
+    Restart(T),
+    /// Resumes the panic, which ends the coroutine along with the task that polls it, like without isolation.
+    Halt,
+}
+
+/// Returns the message of a panic, for the payloads of `panic!` with a literal or with a formatted message.
+pub(crate) fn message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("update panicked")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd, TeaModel,
+    };
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Teacup {
+        sips: u8,
+    }
+
+    impl TeaModel for Teacup {
+        type Action = u8;
+
+        fn update(&mut self, sips: Self::Action) -> Cmd<Self::Action> {
+            assert!(sips > 0, "the cup shatters");
+            // the state is changed before the guard fails, so a skipped action must not leave a trace
+            self.sips += sips;
+            assert!(self.sips <= 5, "the cup overflows");
+            Cmd::none()
+        }
+
+        fn on_panic(&self, message: &str) -> TeaPanicPolicy<Self> {
+            if message == "the cup shatters" {
+                TeaPanicPolicy::Restart(Teacup::default())
+            } else {
+                TeaPanicPolicy::Skip
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn panicking_actions_are_skipped() {
+        let (mut dom, cup) = mount_model(use_tea_model::<Teacup>);
+        for sips in [3, 9, 1] {
+            dom.in_runtime(|| cup.send(sips));
+        }
+        run(&mut dom).await;
+
+        assert_eq!(
+            dom.in_runtime(|| cup.peek().sips),
+            4,
+            "The overflowing sips are dropped, and the model carries on"
+        );
+    }
+
+    #[tokio::test]
+    async fn models_can_restart_after_a_panic() {
+        let (mut dom, cup) = mount_model(use_tea_model::<Teacup>);
+        for sips in [3, 0, 2] {
+            dom.in_runtime(|| cup.send(sips));
+        }
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| cup.peek().sips), 2);
+    }
+}
@@ -0,0 +1,88 @@
+//! Persisting the state of TEA models, e.g. to survive a page reload.
+
+use crate::{apply, use_tea_model_processed, TeaModel, TeaModelInit, TeaModelSignal};
+
+/// A model that can be saved to, and restored from, some storage backend.
+///
+/// The backend is up to the implementor, e.g. `localStorage` on the web or a file on desktop.
+pub trait TeaPersist: Sized {
+    /// Saves the current state.
+    fn save(&self);
+
+    /// Loads the previously saved state, or `None` if there is none.
+    fn load() -> Option<Self>;
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, restoring the persisted state on mount.
+///
+/// The model starts with [`TeaPersist::load`], falling back to [`TeaModelInit::init`] when nothing was saved. After
+/// every update that changed the state, [`TeaPersist::save`] is called; updates that leave the state equal are not
+/// saved again.
+pub fn use_tea_model_persisted<T: TeaModel + TeaModelInit + TeaPersist>() -> TeaModelSignal<T> {
+    use_tea_model_processed(
+        || T::load().unwrap_or_else(T::init),
+        || {
+            Box::new(|state: &mut T, error, action| {
+                let previous = state.clone();
+                let cmd = apply(state, error, action);
+                if T::changed(&previous, state) {
+                    state.save();
+                }
+                cmd
+            })
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::mount_model, Cmd};
+    use std::cell::{Cell, RefCell};
+
+    thread_local! {
+        static STORAGE: RefCell<Option<Teapot>> = const { RefCell::new(None) };
+        static SAVES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Teapot {
+        cups: u8,
+    }
+
+    impl TeaModel for Teapot {
+        type Action = u8;
+
+        fn update(&mut self, cups: Self::Action) -> Cmd<Self::Action> {
+            self.cups = cups;
+            Cmd::none()
+        }
+    }
+
+    impl TeaPersist for Teapot {
+        fn save(&self) {
+            SAVES.set(SAVES.get() + 1);
+            STORAGE.set(Some(self.clone()));
+        }
+
+        fn load() -> Option<Self> {
+            STORAGE.take()
+        }
+    }
+
+    #[test]
+    fn state_is_restored_and_only_saved_when_changed() {
+        STORAGE.set(Some(Teapot { cups: 2 }));
+        let (dom, teapot) = mount_model(use_tea_model_persisted::<Teapot>);
+
+        dom.in_runtime(|| {
+            assert_eq!(teapot.peek().cups, 2, "The saved state is loaded on mount");
+            teapot.send_sync(2);
+            assert_eq!(SAVES.get(), 0, "Unchanged state is not saved");
+            teapot.send_sync(3);
+        });
+        assert_eq!(SAVES.get(), 1);
+        assert_eq!(STORAGE.take(), Some(Teapot { cups: 3 }));
+    }
+}
@@ -0,0 +1,92 @@
+//! TEA models whose urgent actions jump ahead of the queue.
+
+use crate::{apply, new_tea_model, Queue, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::{
+    dioxus_core::current_scope_id,
+    prelude::{use_hook, WritableExt},
+};
+use std::{cmp::Reverse, collections::VecDeque};
+
+/// Ranks an action of a prioritized model; higher ranks are applied first.
+pub(crate) type Priority<A> = Box<dyn Fn(&A) -> u8>;
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel` that applies queued actions by `priority`, highest first.
+///
+/// Whenever the coroutine picks up the actions waiting in the queue, it sorts them by the rank `priority` returns,
+/// so an urgent action like "try again" is applied before the actions sent ahead of it that are still queued.
+/// Actions of the same rank keep their FIFO order, so a model where every action has the same rank behaves like a
+/// plain one. This gives up the strict ordering of [`TeaModelSignal::send`]: only use it for actions that are fine
+/// to overtake the others, typically resets, which can cancel the running commands with
+/// [`TeaModel::cancels_effects`] as well.
+///
+/// Only the actions that are queued at the same time are reordered, after [`TeaModel::coalesce`]; an action that
+/// has been applied already cannot be overtaken, and batches and queries are applied in place, marking the
+/// boundaries of what is sorted. `priority` is only used on the first render.
+pub fn use_tea_model_prioritized<T: TeaModel + TeaModelInit>(
+    priority: impl Fn(&T::Action) -> u8 + 'static,
+) -> TeaModelSignal<T> {
+    use_hook(|| {
+        let model = new_tea_model(current_scope_id(), T::init(), Box::new(apply), Queue::Unbounded);
+        let mut prioritized = model.priority;
+        prioritized.set(Some(Box::new(priority)));
+        model
+    })
+}
+
+/// Sorts `actions` by `priority`, highest first; the sort is stable, so equal ranks stay in FIFO order.
+pub(crate) fn prioritize<A>(actions: &mut VecDeque<A>, priority: &Priority<A>) {
+    actions
+        .make_contiguous()
+        .sort_by_key(|action| Reverse(priority(action)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        Cmd,
+    };
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Pot {
+        brewed: Vec<u8>,
+    }
+
+    enum PotAction {
+        Brew(u8),
+        Reset,
+    }
+
+    impl TeaModel for Pot {
+        type Action = PotAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                PotAction::Brew(cup) => self.brewed.push(cup),
+                PotAction::Reset => self.brewed.clear(),
+            }
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn urgent_actions_jump_ahead_of_the_queue() {
+        let (mut dom, pot) =
+            mount_model(|| use_tea_model_prioritized::<Pot>(|action| u8::from(matches!(action, PotAction::Reset))));
+        dom.in_runtime(|| {
+            pot.send(PotAction::Brew(1));
+            pot.send(PotAction::Brew(2));
+            pot.send(PotAction::Reset);
+            pot.send(PotAction::Brew(3));
+        });
+        run(&mut dom).await;
+
+        assert_eq!(
+            dom.in_runtime(|| pot.peek().brewed.clone()),
+            [1, 2, 3],
+            "The reset is applied first, and the brews keep their order"
+        );
+    }
+}
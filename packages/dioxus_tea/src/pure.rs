@@ -0,0 +1,134 @@
+//! Pure reducers, which take the state by value and return the next one instead of mutating it.
+
+use crate::{use_tea_model, Cmd, TeaModelInit, TeaModelSignal, TryTeaModel};
+use std::{collections::VecDeque, convert::Infallible};
+
+/// Variant of [`TeaModel`](crate::TeaModel) whose reducer is a pure function from the state and an action to the
+/// next state, as in Redux or Elm.
+///
+/// Every `PureTeaModel` is a `TeaModel` through a blanket implementation (by way of [`TryTeaModel`], whose updates
+/// never fail for a pure model), so it works with [`use_pure_tea_model`] as well as all the other hooks of this
+/// crate. A pure reducer has no commands: effects are started from components instead, or by moving the model to
+/// `TeaModel` once it needs them. The tea reducer in the pure style:
+///
+/// ```rust
+/// use dioxus_tea::{drive, PureTeaModel};
+///
+/// #[derive(Default, Clone, PartialEq, Debug)]
+/// enum Status {
+///     #[default]
+///     EmptyCup,
+///     TeaBag,
+///     Water(u8),
+///     TeaReady,
+///     Error,
+/// }
+///
+/// enum MakeTea {
+///     AddTeaBag,
+///     AddWater(u8),
+///     Done,
+/// }
+///
+/// impl PureTeaModel for Status {
+///     type Action = MakeTea;
+///
+///     fn reduce(self, action: Self::Action) -> Self {
+///         match (self, action) {
+///             (Status::EmptyCup, MakeTea::AddTeaBag) => Status::TeaBag,
+///             (Status::TeaBag, MakeTea::AddWater(temperature)) => Status::Water(temperature),
+///             (Status::Water(_), MakeTea::Done) => Status::TeaReady,
+///             // anything else is done in the wrong order
+///             _ => Status::Error,
+///         }
+///     }
+/// }
+///
+/// let actions = [MakeTea::AddTeaBag, MakeTea::AddWater(100), MakeTea::Done];
+/// assert_eq!(drive(Status::default(), actions), Status::TeaReady);
+/// ```
+///
+/// Taking the state by value costs a clone of the state for every action, since the model's signal keeps owning
+/// the current state while the reducer consumes its copy. That is cheap for small states, or ones that share their
+/// large parts in an `Rc` or `Arc`; for big states that change a little with every action, `TeaModel`'s
+/// `&mut self` updates avoid the copy.
+pub trait PureTeaModel: 'static + Clone + PartialEq {
+    /// The type of actions that can be processed by this model.
+    type Action;
+
+    /// Returns the state that follows this one after `action`.
+    #[must_use]
+    fn reduce(self, action: Self::Action) -> Self;
+
+    /// Drops or merges redundant actions before they are applied; see
+    /// [`TeaModel::coalesce`](crate::TeaModel::coalesce).
+    fn coalesce(_pending: &mut VecDeque<Self::Action>) {}
+
+    /// Decides whether an update changed the state; see [`TeaModel::changed`](crate::TeaModel::changed).
+    fn changed(old: &Self, new: &Self) -> bool {
+        old != new
+    }
+}
+
+impl<T: PureTeaModel> TryTeaModel for T {
+    type Action = T::Action;
+    type Error = Infallible;
+
+    /// Replaces the state with the one returned by [`reduce`](PureTeaModel::reduce).
+    fn try_update(&mut self, action: Self::Action) -> Result<Cmd<Self::Action>, Self::Error> {
+        *self = self.clone().reduce(action);
+        Ok(Cmd::none())
+    }
+
+    fn coalesce(pending: &mut VecDeque<Self::Action>) {
+        <T as PureTeaModel>::coalesce(pending);
+    }
+
+    fn changed(old: &Self, new: &Self) -> bool {
+        <T as PureTeaModel>::changed(old, new)
+    }
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given [`PureTeaModel`], which replaces its state with the reduced one for
+/// every action.
+///
+/// This is [`use_tea_model`] for pure reducers, and only spells out the intent: every hook of this crate accepts a
+/// `PureTeaModel`.
+pub fn use_pure_tea_model<T: PureTeaModel + TeaModelInit>() -> TeaModelSignal<T> {
+    use_tea_model()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{mount_model, run};
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Saucer {
+        biscuits: Vec<&'static str>,
+    }
+
+    impl PureTeaModel for Saucer {
+        type Action = &'static str;
+
+        fn reduce(mut self, biscuit: Self::Action) -> Self {
+            self.biscuits.push(biscuit);
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn reduced_states_replace_the_current_one() {
+        let (mut dom, saucer) = mount_model(use_pure_tea_model::<Saucer>);
+        dom.in_runtime(|| {
+            saucer.send("shortbread");
+            saucer.send("digestive");
+        });
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| saucer.peek().biscuits.clone()),
+            ["shortbread", "digestive"]
+        );
+    }
+}
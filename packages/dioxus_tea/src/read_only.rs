@@ -0,0 +1,349 @@
+//! Read-only views of TEA models, for presentational components that should not send actions.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::{
+    dioxus_core::Subscribers,
+    prelude::{use_hook, Memo, ReadSignal, Readable, ReadableExt, ReadableRef, Signal},
+    signals::{BorrowError, UnsyncStorage},
+};
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    #[must_use]
+    /// Returns a memo of the value `f` projects from the model state.
+    ///
+    /// The projection is re-evaluated when the state changes, and subscribers of the memo only re-render when the
+    /// projected value changed. A new memo, owned by the current component, is created on every call, so create it
+    /// once, e.g. `let status = use_hook(|| app_state.map(|state| state.status.clone()));`.
+    pub fn map<U: PartialEq + 'static>(self, f: impl Fn(&T) -> U + 'static) -> Memo<U> {
+        let inner = self.inner;
+        Memo::new(move || f(&inner.read()))
+    }
+
+    #[must_use]
+    /// Returns a read-only view of this model, which can read the state but not send actions.
+    pub fn read_only(&self) -> TeaReadOnlySignal<T> {
+        TeaReadOnlySignal { model: *self }
+    }
+
+    #[must_use]
+    /// Returns the state as a plain [`ReadSignal`], for components and libraries of the Dioxus ecosystem that take
+    /// signal-shaped inputs.
+    ///
+    /// The signal reads the live state of the model, so it follows every update, and subscribes its readers just
+    /// like [`read`](Self::read). Being read-only, it cannot write the state: that still only happens through
+    /// [`send`](Self::send) and the other ways of sending actions. Every call boxes the signal anew, owned by the
+    /// current component, so create it once, e.g. `let state = use_hook(|| app_state.as_signal());`.
+    pub fn as_signal(&self) -> ReadSignal<T> {
+        self.inner.into()
+    }
+}
+
+/// Reads the state like the inner `Signal<T>`, so a model can be passed wherever a `Readable<Target = T>` is
+/// expected, e.g. to the generic helpers of the Dioxus ecosystem.
+///
+/// Only reading is delegated, with the same borrow semantics and subscriptions as the signal: `read` subscribes the
+/// current component, `peek` does not. There is no `Writable` counterpart, since the state only changes through
+/// [`send`](TeaModelSignal::send) and the other ways of sending actions.
+impl<T: TeaModel> Readable for TeaModelSignal<T> {
+    type Storage = UnsyncStorage;
+    type Target = T;
+
+    fn try_read_unchecked(&self) -> Result<ReadableRef<'static, Self>, BorrowError> {
+        self.inner.try_read_unchecked()
+    }
+
+    fn try_peek_unchecked(&self) -> Result<ReadableRef<'static, Self>, BorrowError> {
+        self.inner.try_peek_unchecked()
+    }
+
+    fn subscribers(&self) -> Subscribers {
+        self.inner.subscribers()
+    }
+}
+
+/// A memo of a part of the state, as returned by [`TeaModelSignal::map`] and the lenses of `TeaLenses`, which only
+/// changes when that part does.
+pub type TeaLens<U> = Memo<U>;
+
+/// A read-only view of a [`TeaModelSignal`].
+///
+/// Pass it to presentational components to enforce a unidirectional data flow: they can read the state, but cannot
+/// [`send`](TeaModelSignal::send) actions.
+#[derive(Clone, PartialEq)]
+pub struct TeaReadOnlySignal<T: TeaModel> {
+    model: TeaModelSignal<T>,
+}
+
+impl<T: TeaModel> Copy for TeaReadOnlySignal<T> {}
+
+impl<T: TeaModel> TeaReadOnlySignal<T> {
+    #[must_use]
+    /// Returns a reference to the model state, subscribing the current component to changes.
+    pub fn read(&self) -> ReadableRef<'_, Signal<T>> {
+        self.model.read()
+    }
+
+    #[must_use]
+    /// Returns a reference to the model state without subscribing the current component to changes.
+    pub fn peek(&self) -> ReadableRef<'_, Signal<T>> {
+        self.model.peek()
+    }
+
+    #[must_use]
+    /// Returns a memo of the value `f` projects from the model state; see [`TeaModelSignal::map`].
+    pub fn map<U: PartialEq + 'static>(&self, f: impl Fn(&T) -> U + 'static) -> Memo<U> {
+        self.model.map(f)
+    }
+}
+
+#[must_use]
+/// Returns an owned clone of the state of `model`, re-rendering the current component only when the state changed.
+///
+/// Unlike [`TeaModelSignal::read`], which re-renders the component whenever the state is written, the state is
+/// memoized and compared by `PartialEq`, so actions that leave it unchanged do not re-render. That is convenient for
+/// components that destructure many fields, but it clones the whole state on every change, and once more on every
+/// render; for large states, prefer projecting only what the component needs with [`TeaModelSignal::map`].
+pub fn use_tea_value<T: TeaModel>(model: TeaModelSignal<T>) -> T {
+    let value = use_hook(|| model.map(Clone::clone));
+    value.cloned()
+}
+
+#[must_use]
+/// Returns whether `predicate` holds for the state of `model`, re-rendering the current component only when that
+/// answer flips.
+///
+/// This is [`TeaModelSignal::map`] specialized to conditions, like whether a status is an error: the predicate runs
+/// on every change of the state, which is cheap, but the component only re-renders when its result changed.
+/// Like the initializer of a hook, `predicate` is only used on the first render.
+pub fn use_tea_when<T: TeaModel>(model: TeaModelSignal<T>, predicate: impl Fn(&T) -> bool + 'static) -> bool {
+    let holds = use_hook(|| model.map(predicate));
+    holds()
+}
+
+#[must_use]
+/// Returns the props that `map` derives from the state of `model`, re-rendering the current component only when they
+/// changed.
+///
+/// This is meant for the props of a child component, like which options to show: the props are memoized and compared
+/// by `PartialEq`, so the component that computes them only re-renders when the bundle changed, and the child, whose
+/// props then compare equal as well, is skipped along with it. Passing the whole model down instead re-renders the
+/// child on every write of the state it reads, and couples it to the shape of the state. Like the initializer of a
+/// hook, `map` is only used on the first render.
+pub fn use_tea_props<T: TeaModel, P: Clone + PartialEq + 'static>(
+    model: TeaModelSignal<T>,
+    map: impl Fn(&T) -> P + 'static,
+) -> P {
+    let props = use_hook(|| model.map(map));
+    props.cloned()
+}
+
+impl<T: TeaModel> From<TeaModelSignal<T>> for TeaReadOnlySignal<T> {
+    fn from(model: TeaModelSignal<T>) -> Self {
+        model.read_only()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount, mount_model, run},
+        use_tea_model, Cmd,
+    };
+    use dioxus::prelude::*;
+    use std::cell::{Cell, RefCell};
+
+    thread_local! {
+        static RENDERS: Cell<usize> = const { Cell::new(0) };
+        static VALUE_RENDERS: Cell<usize> = const { Cell::new(0) };
+        static WHEN_RENDERS: Cell<usize> = const { Cell::new(0) };
+        static PROPS_RENDERS: Cell<usize> = const { Cell::new(0) };
+        static PARENT_RENDERS: Cell<usize> = const { Cell::new(0) };
+        static SIGNAL_SIPS: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Cup {
+        sips: u8,
+    }
+
+    impl TeaModel for Cup {
+        type Action = u8;
+
+        fn update(&mut self, sips: Self::Action) -> Cmd<Self::Action> {
+            self.sips += sips;
+            Cmd::none()
+        }
+    }
+
+    #[component]
+    fn Level(cup: TeaReadOnlySignal<Cup>) -> Element {
+        let empty = use_hook(|| cup.map(|cup| cup.sips >= 3));
+        RENDERS.set(RENDERS.get() + 1);
+        rsx! { "{empty}" }
+    }
+
+    #[component]
+    fn Sips(cup: TeaModelSignal<Cup>) -> Element {
+        let Cup { sips } = use_tea_value(cup);
+        VALUE_RENDERS.set(VALUE_RENDERS.get() + 1);
+        rsx! { "{sips}" }
+    }
+
+    #[component]
+    fn Refill(cup: TeaModelSignal<Cup>) -> Element {
+        let empty = use_tea_when(cup, |cup| cup.sips >= 3);
+        WHEN_RENDERS.set(WHEN_RENDERS.get() + 1);
+        rsx! {
+            if empty {
+                "Refill, please"
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    struct Fill {
+        empty: bool,
+    }
+
+    #[component]
+    fn Bottom(fill: Fill) -> Element {
+        PROPS_RENDERS.set(PROPS_RENDERS.get() + 1);
+        rsx! {
+            if fill.empty {
+                "Refill, please"
+            }
+        }
+    }
+
+    /// Stands in for a component of another library, which knows nothing about TEA models.
+    #[component]
+    fn Gauge(level: ReadSignal<Cup>) -> Element {
+        let sips = level.read().sips;
+        SIGNAL_SIPS.with_borrow_mut(|seen| seen.push(sips));
+        rsx! { "{sips}" }
+    }
+
+    /// Stands in for the generic helpers of the ecosystem, which only know about `Readable`.
+    fn sips_of(cup: &impl Readable<Target = Cup>) -> u8 {
+        cup.with_peek(|cup| cup.sips)
+    }
+
+    #[tokio::test]
+    async fn models_can_be_read_generically() {
+        let (mut dom, cup) = mount_model(use_tea_model::<Cup>);
+        dom.in_runtime(|| cup.send(3));
+        run(&mut dom).await;
+        assert_eq!(dom.in_runtime(|| sips_of(&cup)), 3);
+        assert_eq!(
+            dom.in_runtime(|| Readable::try_peek_unchecked(&cup).map(|cup| cup.sips).ok()),
+            Some(3)
+        );
+    }
+
+    #[tokio::test]
+    async fn plain_signals_follow_the_model() {
+        fn app() -> Element {
+            let model = use_tea_model::<Cup>();
+            use_context_provider(|| model);
+            let level = use_hook(|| model.as_signal());
+            rsx! {
+                Gauge { level }
+            }
+        }
+
+        let (mut dom, cup) = mount::<TeaModelSignal<Cup>>(app);
+        dom.in_runtime(|| cup.send(2));
+        run(&mut dom).await;
+
+        assert_eq!(SIGNAL_SIPS.take(), [0, 2]);
+    }
+
+    #[tokio::test]
+    async fn stable_props_do_not_rerender_the_child() {
+        fn app() -> Element {
+            let model = use_tea_model::<Cup>();
+            use_context_provider(|| model);
+            let fill = use_tea_props(model, |cup| Fill { empty: cup.sips >= 3 });
+            PARENT_RENDERS.set(PARENT_RENDERS.get() + 1);
+            rsx! {
+                Bottom { fill }
+            }
+        }
+
+        let (mut dom, cup) = mount::<TeaModelSignal<Cup>>(app);
+        for sips in [1, 1, 1] {
+            dom.in_runtime(|| cup.send(sips));
+            run(&mut dom).await;
+        }
+
+        assert_eq!(PARENT_RENDERS.get(), 2, "Only emptying the cup changes the props");
+        assert_eq!(PROPS_RENDERS.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn conditions_only_rerender_when_they_flip() {
+        fn app() -> Element {
+            let model = use_tea_model::<Cup>();
+            use_context_provider(|| model);
+            rsx! {
+                Refill { cup: model }
+            }
+        }
+
+        let (mut dom, cup) = mount::<TeaModelSignal<Cup>>(app);
+        for sips in [1, 1, 1, 1] {
+            dom.in_runtime(|| cup.send(sips));
+            run(&mut dom).await;
+        }
+
+        assert_eq!(
+            WHEN_RENDERS.get(),
+            2,
+            "Only the sip that empties the cup flips the condition"
+        );
+    }
+
+    #[tokio::test]
+    async fn values_only_rerender_when_the_state_changes() {
+        fn app() -> Element {
+            let model = use_tea_model::<Cup>();
+            use_context_provider(|| model);
+            rsx! {
+                Sips { cup: model }
+            }
+        }
+
+        let (mut dom, cup) = mount::<TeaModelSignal<Cup>>(app);
+        for sips in [0, 1, 0] {
+            dom.in_runtime(|| cup.send(sips));
+            run(&mut dom).await;
+        }
+
+        assert_eq!(VALUE_RENDERS.get(), 2, "Taking no sips does not re-render the value");
+    }
+
+    #[tokio::test]
+    async fn views_only_rerender_when_the_projection_changes() {
+        fn app() -> Element {
+            let model = use_tea_model::<Cup>();
+            use_context_provider(|| model);
+            rsx! {
+                Level { cup: model.read_only() }
+            }
+        }
+
+        let (mut dom, cup) = mount::<TeaModelSignal<Cup>>(app);
+        for sips in [1, 1, 1] {
+            dom.in_runtime(|| cup.send(sips));
+            run(&mut dom).await;
+        }
+
+        assert_eq!(dom.in_runtime(|| cup.read_only().peek().sips), 3);
+        assert_eq!(
+            RENDERS.get(),
+            2,
+            "Only the sip that empties the cup re-renders the view"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Recording the actions of a TEA model, to replay them later, e.g. to reproduce a bug report.
+
+use crate::{apply, source, use_tea_model_processed, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::prelude::{use_hook, CopyValue, ReadableExt, WritableExt};
+use std::ops::Deref;
+
+/// A [`TeaModelSignal`] that records every action it applies, in order.
+///
+/// It dereferences to the underlying `TeaModelSignal`, so actions are sent and state is read just like with a plain
+/// model.
+#[derive(Clone, PartialEq)]
+pub struct TeaRecorder<T: TeaModel> {
+    model: TeaModelSignal<T>,
+    recorded: CopyValue<Vec<(&'static str, <T as TeaModel>::Action)>>,
+}
+
+impl<T: TeaModel> Copy for TeaRecorder<T> {}
+
+impl<T: TeaModel> TeaRecorder<T>
+where
+    T::Action: Clone,
+{
+    #[must_use]
+    /// Returns the underlying model signal, e.g. to pass it to components that do not need the recording.
+    pub fn model(&self) -> TeaModelSignal<T> {
+        self.model
+    }
+
+    #[must_use]
+    /// Returns every action applied so far, in the order they were applied.
+    ///
+    /// This includes the actions produced by commands. Reading the recording does not subscribe the current
+    /// component.
+    pub fn recorded(&self) -> Vec<T::Action> {
+        self.recorded.peek().iter().map(|(_, action)| action.clone()).collect()
+    }
+
+    #[must_use]
+    /// Returns every action applied so far like [`recorded`](Self::recorded), together with the source it was sent
+    /// from, see [`send_from`](TeaModelSignal::send_from), so a replay can tell where every action came from.
+    pub fn recorded_with_sources(&self) -> Vec<(&'static str, T::Action)> {
+        self.recorded.peek().clone()
+    }
+
+    #[must_use]
+    /// Applies `actions` to a fresh `T::init()` and returns the final state.
+    ///
+    /// Commands returned by `update` are not run, and neither are the follow-ups of
+    /// [`Cmd::dispatch_next`](crate::Cmd::dispatch_next): the actions they produced are part of the recording already.
+    pub fn replay(actions: &[T::Action]) -> T
+    where
+        T: TeaModelInit,
+    {
+        let mut state = T::init();
+        for action in actions.iter().cloned() {
+            let _ = state.try_apply(action);
+        }
+        state
+    }
+}
+
+impl<T: TeaModel> Deref for TeaRecorder<T> {
+    type Target = TeaModelSignal<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.model
+    }
+}
+
+#[must_use]
+/// Creates a new `TeaRecorder` for the given `TeaModel`, recording every action that is applied to it.
+///
+/// The recording grows for as long as the model lives, so this is meant for debugging sessions rather than for
+/// production builds.
+pub fn use_tea_model_recorded<T: TeaModel + TeaModelInit>() -> TeaRecorder<T>
+where
+    T::Action: Clone,
+{
+    let mut recorded = use_hook(|| CopyValue::new(Vec::new()));
+
+    let model = use_tea_model_processed(T::init, move || {
+        Box::new(move |state: &mut T, error, action: T::Action| {
+            recorded.write().push((source::current(), action.clone()));
+            apply(state, error, action)
+        })
+    });
+
+    TeaRecorder { model, recorded }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        Cmd,
+    };
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Pot {
+        spoons: Vec<u8>,
+    }
+
+    impl TeaModel for Pot {
+        type Action = u8;
+
+        fn update(&mut self, spoon: Self::Action) -> Cmd<Self::Action> {
+            self.spoons.push(spoon);
+            Cmd::none()
+        }
+    }
+
+    #[test]
+    fn replaying_the_recording_reproduces_the_state() {
+        let (dom, pot) = mount_model(use_tea_model_recorded::<Pot>);
+        dom.in_runtime(|| {
+            for spoon in [3, 1, 2] {
+                pot.send_sync(spoon);
+            }
+            assert_eq!(pot.recorded(), [3, 1, 2]);
+            assert_eq!(TeaRecorder::<Pot>::replay(&pot.recorded()), *pot.peek());
+        });
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Kettle {
+        steps: Vec<u8>,
+    }
+
+    impl TeaModel for Kettle {
+        type Action = u8;
+
+        fn update(&mut self, step: Self::Action) -> Cmd<Self::Action> {
+            self.steps.push(step);
+            if step == 1 {
+                Cmd::dispatch_next(2)
+            } else {
+                Cmd::none()
+            }
+        }
+    }
+
+    #[test]
+    fn replays_do_not_apply_recorded_follow_ups_twice() {
+        let (dom, kettle) = mount_model(use_tea_model_recorded::<Kettle>);
+        dom.in_runtime(|| {
+            kettle.send_sync(1);
+            assert_eq!(kettle.recorded(), [1, 2], "The follow-up is recorded");
+            assert_eq!(TeaRecorder::<Kettle>::replay(&kettle.recorded()).steps, [1, 2]);
+        });
+    }
+
+    #[tokio::test]
+    async fn recordings_show_where_actions_came_from() {
+        let (mut dom, pot) = mount_model(use_tea_model_recorded::<Pot>);
+        dom.in_runtime(|| {
+            pot.send_from(1, "sugar-button");
+            pot.send(2);
+        });
+        run(&mut dom).await;
+
+        assert_eq!(
+            dom.in_runtime(|| pot.recorded_with_sources()),
+            [("sugar-button", 1), (crate::UNKNOWN_SOURCE, 2)]
+        );
+    }
+}
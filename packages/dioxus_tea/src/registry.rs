@@ -0,0 +1,124 @@
+//! A single place to fetch the app-wide TEA models of many types from.
+
+use crate::{global::global_tea_model, TeaModel, TeaModelInit, TeaModelSignal};
+
+/// The app-wide models of an app, one per type, as returned by [`use_tea_registry`].
+///
+/// The registry is a `Copy` handle, so it can be moved into event handlers and passed to children freely.
+#[derive(Clone, Copy, PartialEq)]
+pub struct TeaRegistry {
+    _private: (),
+}
+
+impl TeaRegistry {
+    #[must_use]
+    /// Returns the model of type `T`, creating it on the first call.
+    ///
+    /// The models are created lazily, seeded with [`TeaModelInit::init`] the first time any component asks for
+    /// them, and are singletons within the app: every later call returns the same handle. They are the models of
+    /// [`use_global_tea_model`](crate::use_global_tea_model), stored in the root context, so both ways of fetching a
+    /// model of type `T` return the same one, and it lives as long as the app, no matter which component created it.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside of a Dioxus runtime.
+    pub fn get<T: TeaModel + TeaModelInit>(&self) -> TeaModelSignal<T> {
+        global_tea_model()
+    }
+}
+
+#[must_use]
+/// Returns the app's [`TeaRegistry`].
+///
+/// Every component gets an equivalent handle, and it replaces a `use_context_provider` call per model: components
+/// fetch the models they need with [`get`](TeaRegistry::get) instead.
+pub fn use_tea_registry() -> TeaRegistry {
+    TeaRegistry { _private: () }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount, run},
+        use_global_tea_model, Cmd,
+    };
+    use dioxus::prelude::*;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Teapot {
+        cups: u8,
+    }
+
+    impl TeaModel for Teapot {
+        type Action = u8;
+
+        fn update(&mut self, cups: Self::Action) -> Cmd<Self::Action> {
+            self.cups += cups;
+            Cmd::none()
+        }
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Biscuits {
+        left: Vec<String>,
+    }
+
+    impl TeaModel for Biscuits {
+        type Action = String;
+
+        fn update(&mut self, biscuit: Self::Action) -> Cmd<Self::Action> {
+            self.left.push(biscuit);
+            Cmd::none()
+        }
+    }
+
+    #[component]
+    fn Guest() -> Element {
+        let registry = use_tea_registry();
+        use_hook(|| {
+            registry.get::<Teapot>().send(1);
+            registry.get::<Biscuits>().send("shortbread".to_string());
+        });
+        rsx! {}
+    }
+
+    #[tokio::test]
+    async fn every_type_is_a_singleton_within_the_registry() {
+        fn app() -> Element {
+            let registry = use_tea_registry();
+            use_context_provider(|| registry);
+            rsx! {
+                Guest {}
+                Guest {}
+            }
+        }
+
+        let (mut dom, registry) = mount::<TeaRegistry>(app);
+        run(&mut dom).await;
+
+        let (teapot, biscuits) = dom.in_runtime(|| (registry.get::<Teapot>(), registry.get::<Biscuits>()));
+        assert!(teapot == dom.in_runtime(|| registry.get::<Teapot>()));
+        assert_eq!(dom.in_runtime(|| teapot.peek().cups), 2, "Both guests share the teapot");
+        assert_eq!(
+            dom.in_runtime(|| biscuits.peek().left.clone()),
+            ["shortbread", "shortbread"]
+        );
+    }
+
+    #[tokio::test]
+    async fn registered_models_are_the_global_ones() {
+        #[derive(Clone, Copy)]
+        struct Teapots(TeaModelSignal<Teapot>, TeaModelSignal<Teapot>);
+
+        fn app() -> Element {
+            let global = use_global_tea_model::<Teapot>();
+            let registered = use_tea_registry().get::<Teapot>();
+            use_context_provider(|| Teapots(global, registered));
+            rsx! {}
+        }
+
+        let (_dom, Teapots(global, registered)) = mount::<Teapots>(app);
+        assert!(global == registered, "There is one app-wide teapot");
+    }
+}
@@ -0,0 +1,178 @@
+//! Actions that are sent after a delay, once or repeatedly.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::{
+    dioxus_core::{Runtime, Task},
+    prelude::{CopyValue, WritableExt},
+};
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+/// A handle to an action scheduled with [`TeaModelSignal::send_after`].
+///
+/// Dropping the handle does not cancel the action; call [`cancel`](Self::cancel) for that.
+#[derive(Clone, Copy, PartialEq)]
+pub struct ScheduledAction {
+    task: Task,
+    scheduled: CopyValue<Vec<Task>>,
+}
+
+impl ScheduledAction {
+    /// Cancels the action, unless it has been sent already.
+    pub fn cancel(self) {
+        let mut scheduled = self.scheduled;
+        scheduled.write().retain(|task| *task != self.task);
+        self.task.cancel();
+    }
+}
+
+#[must_use = "dropping the handle stops the interval right away"]
+/// A handle to the interval started with [`TeaModelSignal::send_interval`], which keeps it running.
+///
+/// The interval stops when the handle is dropped or [`stop`](Self::stop) is called.
+pub struct IntervalHandle {
+    task: Task,
+    stopped: Rc<Cell<bool>>,
+}
+
+impl IntervalHandle {
+    /// Stops the interval, so no further action is sent.
+    pub fn stop(self) {}
+}
+
+impl Drop for IntervalHandle {
+    fn drop(&mut self) {
+        // the flag stops the loop at its next tick even when the handle is dropped outside of the runtime, where the
+        // task cannot be cancelled
+        self.stopped.set(true);
+        if Runtime::try_current().is_some() {
+            self.task.cancel();
+        }
+    }
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Sends the action once `delay` has passed, returning a handle to cancel it before that.
+    ///
+    /// Once the delay has passed, the action is delivered with [`send`](Self::send), so it is ordered with all
+    /// other queued actions. The timer is tied to the component that owns the model, and is cleared by
+    /// [`reset`](Self::reset) and [`reset_with`](Self::reset_with), so an action scheduled for the old state never
+    /// reaches the reset one.
+    pub fn send_after(&self, action: T::Action, delay: Duration) -> ScheduledAction {
+        let model = *self;
+        let mut scheduled = self.scheduled;
+        let clock = self.clock();
+
+        let task = self.spawn(async move {
+            clock.sleep(delay).await;
+            let current = Runtime::current().current_task();
+            scheduled.write().retain(|task| Some(*task) != current);
+            model.send(action);
+        });
+        scheduled.write().push(task);
+
+        ScheduledAction { task, scheduled }
+    }
+
+    /// Sends the action returned by `make_action` every `period`, until the returned handle is dropped.
+    ///
+    /// The first action is sent once the first `period` has passed. Like with [`send_after`](Self::send_after), the
+    /// actions are delivered with [`send`](Self::send) and the interval is tied to the component that owns the
+    /// model, but it is not affected by [`reset`](Self::reset): keep the handle for as long as the ticks are wanted,
+    /// e.g. in a signal, next to the brewing state that needs them.
+    pub fn send_interval(&self, make_action: impl Fn() -> T::Action + 'static, period: Duration) -> IntervalHandle {
+        let model = *self;
+        let clock = self.clock();
+        let stopped = Rc::new(Cell::new(false));
+
+        let task = self.spawn({
+            let stopped = stopped.clone();
+            async move {
+                loop {
+                    clock.sleep(period).await;
+                    if stopped.get() {
+                        break;
+                    }
+                    model.send(make_action());
+                }
+            }
+        });
+
+        IntervalHandle { task, stopped }
+    }
+
+    /// Cancels every action scheduled with [`send_after`](Self::send_after) that has not been sent yet.
+    pub(crate) fn cancel_scheduled(&self) {
+        let mut scheduled = self.scheduled;
+        for task in scheduled.write().drain(..) {
+            task.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run, run_for},
+        use_tea_model, Cmd,
+    };
+
+    #[derive(Default, Clone, PartialEq)]
+    struct Teapot {
+        done: bool,
+        ticks: usize,
+    }
+
+    impl TeaModel for Teapot {
+        type Action = ();
+
+        fn update(&mut self, (): Self::Action) -> Cmd<Self::Action> {
+            self.done = true;
+            self.ticks += 1;
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn scheduled_actions_are_sent_after_the_delay_unless_cancelled() {
+        let (mut dom, teapot) = mount_model(use_tea_model::<Teapot>);
+
+        dom.in_runtime(|| teapot.send_after((), Duration::from_millis(10)).cancel());
+        run(&mut dom).await;
+        assert!(
+            !dom.in_runtime(|| teapot.peek().done),
+            "The cancelled action is never sent"
+        );
+
+        dom.in_runtime(|| teapot.send_after((), Duration::from_millis(10)));
+        run(&mut dom).await;
+        assert!(dom.in_runtime(|| teapot.peek().done));
+    }
+
+    #[tokio::test]
+    async fn resetting_clears_the_scheduled_actions() {
+        let (mut dom, teapot) = mount_model(use_tea_model::<Teapot>);
+
+        dom.in_runtime(|| {
+            teapot.send_after((), Duration::from_millis(10));
+            teapot.reset();
+        });
+        run(&mut dom).await;
+        assert!(
+            !dom.in_runtime(|| teapot.peek().done),
+            "The timer does not outlive the reset"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn intervals_tick_until_the_handle_is_dropped() {
+        let (mut dom, teapot) = mount_model(use_tea_model::<Teapot>);
+
+        let ticking = dom.in_runtime(|| teapot.send_interval(|| (), Duration::from_millis(10)));
+        run_for(&mut dom, Duration::from_millis(35)).await;
+        dom.in_runtime(|| drop(ticking));
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| teapot.peek().ticks), 3);
+    }
+}
@@ -0,0 +1,101 @@
+//! Tagging actions with the source that sent them, e.g. to debug why an action fired twice.
+
+use crate::{Envelope, TeaModel, TeaModelSignal};
+use dioxus::prelude::WritableExt;
+use futures_channel::mpsc;
+use futures_util::Stream;
+use std::cell::Cell;
+
+/// The source of the actions sent with a plain [`send`](TeaModelSignal::send).
+pub const UNKNOWN_SOURCE: &str = "unknown";
+
+thread_local! {
+    /// The source of the action that is currently being applied.
+    static SOURCE: Cell<&'static str> = const { Cell::new(UNKNOWN_SOURCE) };
+}
+
+/// Runs `f` while the action of `source` is being applied.
+pub(crate) fn with_source<R>(source: &'static str, f: impl FnOnce() -> R) -> R {
+    // a tagged action that applies another model's action right away tags that one as well, which is what it is
+    let outer = SOURCE.replace(source);
+    let result = f();
+    SOURCE.set(outer);
+    result
+}
+
+/// Returns the source of the action that is currently being applied.
+pub(crate) fn current() -> &'static str {
+    SOURCE.get()
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Sends an action like [`send`](Self::send), tagged with the `source` that produced it, e.g. `"kettle-timer"`.
+    ///
+    /// The tag shows up wherever the model reports its actions: in the events of a model from
+    /// [`use_tea_model_traced`](crate::use_tea_model_traced), the labels of
+    /// [`TeaDevtools`](crate::TeaDevtools), the recording of a [`TeaRecorder`](crate::TeaRecorder) and the streams
+    /// of [`action_stream_with_source`](Self::action_stream_with_source). Actions sent with `send`, and the ones
+    /// produced by commands, are tagged [`UNKNOWN_SOURCE`].
+    ///
+    /// A tagged action keeps its place in the queue, but is applied on its own: [`TeaModel::coalesce`] never sees
+    /// it, like a batch.
+    pub fn send_from(&self, action: T::Action, source: &'static str) {
+        let _ = self.enqueue(Envelope::From(action, source));
+    }
+
+    /// Returns a stream like [`action_stream`](Self::action_stream), which yields every action together with the
+    /// source it was sent from, see [`send_from`](Self::send_from).
+    pub fn action_stream_with_source(&self) -> impl Stream<Item = (&'static str, T::Action)>
+    where
+        T::Action: Clone,
+    {
+        let (tx, rx) = mpsc::unbounded();
+        let mut observers = self.observers;
+        observers.write().push(Box::new(move |action: &T::Action, source| {
+            tx.unbounded_send((source, action.clone())).is_ok()
+        }));
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+    use futures_util::{FutureExt, StreamExt};
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Kettle {
+        boiled: u8,
+    }
+
+    impl TeaModel for Kettle {
+        type Action = ();
+
+        fn update(&mut self, (): Self::Action) -> Cmd<Self::Action> {
+            self.boiled += 1;
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn actions_carry_their_source() {
+        let (mut dom, kettle) = mount_model(use_tea_model::<Kettle>);
+        let mut actions = dom.in_runtime(|| kettle.action_stream_with_source());
+        dom.in_runtime(|| {
+            kettle.send_from((), "button");
+            kettle.send(());
+            kettle.send_from((), "timer");
+        });
+        run(&mut dom).await;
+
+        let sources: Vec<_> = std::iter::from_fn(|| actions.next().now_or_never().flatten())
+            .map(|(source, ())| source)
+            .collect();
+        assert_eq!(sources, ["button", UNKNOWN_SOURCE, "timer"]);
+        assert_eq!(dom.in_runtime(|| kettle.peek().boiled), 3);
+    }
+}
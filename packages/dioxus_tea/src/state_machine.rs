@@ -0,0 +1,208 @@
+//! TEA models whose transitions are validated against a declared set of rules before `update` runs.
+
+use crate::{apply, use_tea_model_processed, Cmd, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::prelude::{ReadableExt, WritableExt};
+use std::{error::Error, fmt, rc::Rc};
+
+/// A model that declares which actions are allowed from which states.
+///
+/// A model created with [`use_tea_state_machine`] checks every action with [`allowed`](Self::allowed) before it
+/// reaches `update`, so the reducer only handles legal transitions. The rules are usually declared with a
+/// [`TransitionTable`]:
+///
+/// ```rust,ignore
+/// static TRANSITIONS: LazyLock<TransitionTable<AppState, AppStatusUpdate>> = LazyLock::new(|| {
+///     TransitionTable::new()
+///         .allow(
+///             |action| matches!(action, AppStatusUpdate::AddTeaBag(_)),
+///             |from| from.status == Status::EmptyCup,
+///         )
+///         .allow(
+///             |action| matches!(action, AppStatusUpdate::AddWater(_)),
+///             |from| matches!(from.status, Status::TeaBag(_)),
+///         )
+/// });
+///
+/// impl TeaStateMachine for AppState {
+///     fn allowed(from: &Self, action: &Self::Action) -> bool {
+///         TRANSITIONS.allows(from, action)
+///     }
+/// }
+/// ```
+pub trait TeaStateMachine: TeaModel {
+    /// Returns `true` if `action` may be applied to the state `from`.
+    fn allowed(from: &Self, action: &Self::Action) -> bool;
+}
+
+/// The error routed to the error sink when a [`TeaStateMachine`] rejected an action.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IllegalTransition;
+
+impl fmt::Display for IllegalTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the action is not allowed in the current state")
+    }
+}
+
+impl Error for IllegalTransition {}
+
+/// A set of rules for the actions a [`TeaStateMachine`] allows, declared with [`allow`](Self::allow).
+///
+/// Every rule matches a kind of action, and the states it is allowed from. Actions that no rule matches are not
+/// restricted; an action that rules match is allowed from the states that any of them accepts. The rules are plain
+/// function pointers, so a table can be built once and kept in a `static`.
+pub struct TransitionTable<S, A> {
+    rules: Vec<Rule<S, A>>,
+}
+
+/// Matches a kind of action, and the states it is allowed from.
+type Rule<S, A> = (fn(&A) -> bool, fn(&S) -> bool);
+
+impl<S, A> Default for TransitionTable<S, A> {
+    fn default() -> Self {
+        Self { rules: Vec::new() }
+    }
+}
+
+impl<S, A> TransitionTable<S, A> {
+    #[must_use]
+    /// Creates a table without rules, which allows every action.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    /// Allows the actions that `action` matches from the states that `from` matches, and from no other state unless
+    /// another rule allows it.
+    pub fn allow(mut self, action: fn(&A) -> bool, from: fn(&S) -> bool) -> Self {
+        self.rules.push((action, from));
+        self
+    }
+
+    #[must_use]
+    /// Returns `true` if the rules allow `action` from the state `from`.
+    pub fn allows(&self, from: &S, action: &A) -> bool {
+        let mut rules = self.rules.iter().filter(|(matches, _)| matches(action)).peekable();
+        rules.peek().is_none() || rules.any(|(_, allowed_from)| allowed_from(from))
+    }
+}
+
+impl<T: TeaStateMachine> TeaModelSignal<T> {
+    #[must_use]
+    /// Returns `true` if the most recently processed action was rejected as an [`IllegalTransition`].
+    ///
+    /// This subscribes the current component to the error sink; like an error, the rejection is cleared as soon as
+    /// a subsequent action is applied.
+    pub fn illegal_transition(&self) -> bool {
+        self.error
+            .read()
+            .as_ref()
+            .is_some_and(|error| error.is::<IllegalTransition>())
+    }
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given [`TeaStateMachine`], which rejects the actions its rules do not
+/// allow.
+///
+/// A rejected action never reaches `update`: the state is left as it is, no command runs, the model's
+/// [`on_rejected`](TeaModel::on_rejected) hook is called, and an [`IllegalTransition`] is routed to the error sink,
+/// where [`illegal_transition`](TeaModelSignal::illegal_transition) finds it. The next allowed action clears it.
+///
+/// For a [`TryTeaModel`](crate::TryTeaModel), the rules are checked before `try_update`, and both share the error
+/// sink, which holds whichever failed last: [`errors`](TeaModelSignal::errors) only reports the errors of
+/// `try_update`, so it returns `None` for a rejected transition.
+pub fn use_tea_state_machine<T: TeaStateMachine + TeaModelInit>() -> TeaModelSignal<T> {
+    use_tea_model_processed(T::init, || {
+        Box::new(|state: &mut T, mut error, action| {
+            if T::allowed(state, &action) {
+                return apply(state, error, action);
+            }
+            state.on_rejected(&action);
+            error.set(Some(Rc::new(IllegalTransition)));
+            Cmd::none()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{mount_model, run};
+    use std::sync::LazyLock;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    enum Kettle {
+        #[default]
+        Empty,
+        Filled,
+        Boiling,
+    }
+
+    enum KettleAction {
+        Fill,
+        Boil,
+        Empty,
+    }
+
+    impl TeaModel for Kettle {
+        type Action = KettleAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            *self = match action {
+                KettleAction::Fill => Kettle::Filled,
+                KettleAction::Boil => Kettle::Boiling,
+                KettleAction::Empty => Kettle::Empty,
+            };
+            Cmd::none()
+        }
+    }
+
+    static TRANSITIONS: LazyLock<TransitionTable<Kettle, KettleAction>> = LazyLock::new(|| {
+        TransitionTable::new()
+            .allow(
+                |action| matches!(action, KettleAction::Fill),
+                |from| *from == Kettle::Empty,
+            )
+            .allow(
+                |action| matches!(action, KettleAction::Boil),
+                |from| *from == Kettle::Filled,
+            )
+    });
+
+    impl TeaStateMachine for Kettle {
+        fn allowed(from: &Self, action: &Self::Action) -> bool {
+            TRANSITIONS.allows(from, action)
+        }
+    }
+
+    #[test]
+    fn tables_only_restrict_the_actions_they_match() {
+        assert!(Kettle::allowed(&Kettle::Empty, &KettleAction::Fill));
+        assert!(!Kettle::allowed(&Kettle::Filled, &KettleAction::Fill));
+        assert!(!Kettle::allowed(&Kettle::Empty, &KettleAction::Boil));
+        assert!(
+            Kettle::allowed(&Kettle::Boiling, &KettleAction::Empty),
+            "Emptying is always allowed"
+        );
+    }
+
+    #[tokio::test]
+    async fn illegal_transitions_are_rejected_into_the_error_sink() {
+        let (mut dom, kettle) = mount_model(use_tea_state_machine::<Kettle>);
+        dom.in_runtime(|| kettle.send(KettleAction::Boil));
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| (kettle.peek().clone(), kettle.illegal_transition())),
+            (Kettle::Empty, true),
+            "An empty kettle cannot boil"
+        );
+
+        dom.in_runtime(|| kettle.send(KettleAction::Fill));
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| (kettle.peek().clone(), kettle.illegal_transition())),
+            (Kettle::Filled, false)
+        );
+    }
+}
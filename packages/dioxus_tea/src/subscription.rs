@@ -0,0 +1,162 @@
+//! Subscriptions, which turn external event sources into actions, modelled after Elm's
+//! [`Sub`](https://package.elm-lang.org/packages/elm/core/latest/Platform-Sub).
+
+use crate::{Envelope, TeaModel, TeaModelSignal};
+use dioxus::{
+    dioxus_core::Task,
+    prelude::{ReadableExt, WritableExt},
+};
+use futures_util::{Stream, StreamExt};
+
+/// A handle to a subscription started with [`TeaModelSignal::subscribe`].
+///
+/// Dropping the handle does not end the subscription; call [`unsubscribe`](Self::unsubscribe) for that.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Subscription {
+    task: Task,
+}
+
+impl Subscription {
+    /// Ends the subscription, so no further item of its source is sent.
+    pub fn unsubscribe(self) {
+        self.task.cancel();
+    }
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Sends every item of `source` as an action, e.g. the messages of a websocket feed.
+    ///
+    /// The items are delivered with [`send`](Self::send), so they are ordered with all other queued actions. The
+    /// subscription ends when `source` does, when [`unsubscribe`](Subscription::unsubscribe) is called, or when the
+    /// component that owns the model unmounts, which drops the source along with the task that polls it.
+    pub fn subscribe(&self, source: impl Stream<Item = T::Action> + 'static) -> Subscription {
+        let model = *self;
+        let task = self.spawn(async move {
+            let mut source = std::pin::pin!(source);
+            while let Some(action) = source.next().await {
+                model.send(action);
+            }
+        });
+        Subscription { task }
+    }
+
+    #[must_use]
+    /// **Advanced:** returns a handle on the model's own action queue, for interop with code that should only be able
+    /// to send, like a custom subscription task.
+    ///
+    /// Sending through the handle is equivalent to [`send`](Self::send), except that it bypasses the bound and the
+    /// [overflow policy](crate::TeaOverflow) of a bounded or configured queue, like the actions of commands do. The
+    /// actions are appended to the same queue as the ones sent directly and counted as
+    /// [pending](Self::pending_len), and they still go through the model's processor, including any middleware,
+    /// recording or history, as that is how the model applies an action. Like the model, the handle is `Copy` and
+    /// bound to the thread of its component; sending through it is a no-op once the component unmounts or the model
+    /// is [shut down](Self::shutdown). Prefer `send` everywhere else.
+    ///
+    /// ```rust,ignore
+    /// let orders = app_state.coroutine();
+    /// spawn(async move {
+    ///     while let Some(order) = incoming_orders.next().await {
+    ///         orders.send(AppStatusUpdate::AddTeaBag(order.tea_type));
+    ///     }
+    /// });
+    /// ```
+    pub fn coroutine(&self) -> TeaCoroutine<T> {
+        TeaCoroutine { model: *self }
+    }
+}
+
+/// A handle on the action queue of a model, see [`TeaModelSignal::coroutine`].
+#[derive(Clone, PartialEq)]
+pub struct TeaCoroutine<T: TeaModel> {
+    model: TeaModelSignal<T>,
+}
+
+impl<T: TeaModel> Copy for TeaCoroutine<T> {}
+
+impl<T: TeaModel> TeaCoroutine<T> {
+    /// Appends `action` to the model's queue, behind every message that was queued before.
+    pub fn send(&self, action: T::Action) {
+        let model = self.model;
+        model.wake();
+        // the queue is gone along with the component that owned the model, or closed by a shutdown
+        let sent = model
+            .tx
+            .try_peek()
+            .is_ok_and(|tx| tx.unbounded_send(Envelope::Action(action)).is_ok());
+        if sent {
+            let mut queued = model.queued;
+            *queued.write() += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+    use futures_channel::mpsc;
+    use futures_util::stream;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Orders {
+        cups: Vec<u8>,
+    }
+
+    impl TeaModel for Orders {
+        type Action = u8;
+
+        fn update(&mut self, cup: Self::Action) -> Cmd<Self::Action> {
+            self.cups.push(cup);
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn items_of_the_source_are_sent_as_actions() {
+        let (mut dom, orders) = mount_model(use_tea_model::<Orders>);
+        dom.in_runtime(|| orders.subscribe(stream::iter([1, 2, 3])));
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| orders.peek().cups.clone()), [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn unsubscribing_stops_the_source() {
+        let (mut dom, orders) = mount_model(use_tea_model::<Orders>);
+        let (tx, rx) = mpsc::unbounded();
+        let subscription = dom.in_runtime(|| orders.subscribe(rx));
+
+        tx.unbounded_send(1).unwrap();
+        run(&mut dom).await;
+        dom.in_runtime(|| subscription.unsubscribe());
+        assert!(
+            tx.unbounded_send(2).is_err(),
+            "The source is dropped along with the subscription"
+        );
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| orders.peek().cups.clone()), [1]);
+    }
+
+    #[tokio::test]
+    async fn the_coroutine_handle_shares_the_queue_of_the_model() {
+        let (mut dom, orders) = mount_model(use_tea_model::<Orders>);
+        dom.in_runtime(|| {
+            let coroutine = orders.coroutine();
+            coroutine.send(1);
+            orders.send(2);
+            coroutine.send(3);
+            assert_eq!(orders.pending_len(), 3);
+        });
+        run(&mut dom).await;
+
+        assert_eq!(
+            dom.in_runtime(|| orders.peek().cups.clone()),
+            [1, 2, 3],
+            "The handle's actions are ordered with the ones sent directly"
+        );
+    }
+}
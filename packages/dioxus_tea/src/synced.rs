@@ -0,0 +1,304 @@
+//! TEA models whose actions are shared with other clients, enabled with the `ws` or the `broadcast` feature.
+
+use crate::{apply, use_tea_model_processed, Cmd, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::prelude::use_hook;
+use dioxus_sdk::time::sleep;
+use futures_channel::mpsc::UnboundedSender;
+use futures_util::{stream::LocalBoxStream, Stream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+/// The longest pause between two attempts to reconnect.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// An open connection of a synced model, carrying actions serialized as JSON text in both directions.
+pub struct TeaConnection {
+    outgoing: UnboundedSender<String>,
+    incoming: LocalBoxStream<'static, String>,
+}
+
+impl TeaConnection {
+    #[must_use]
+    /// Creates a connection that sends the local actions to `outgoing`, and applies the ones yielded by `incoming`.
+    ///
+    /// The connection is considered closed once `incoming` ends.
+    pub fn new(outgoing: UnboundedSender<String>, incoming: impl Stream<Item = String> + 'static) -> Self {
+        Self {
+            outgoing,
+            incoming: incoming.boxed_local(),
+        }
+    }
+}
+
+/// Opens the connections of a model created with [`use_tea_model_synced_with`].
+///
+/// Implement this for transports other than the built-in ones, the `WebSocket` of the `ws` feature and the
+/// `BroadcastChannel` of the `broadcast` feature, e.g. for a websocket of the browser, a TLS-enabled client, or a
+/// mock in tests.
+#[allow(async_fn_in_trait)]
+pub trait TeaTransport: 'static {
+    /// Opens a connection, or returns `None` if that failed, to be retried later.
+    async fn connect(&mut self) -> Option<TeaConnection>;
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, sharing its actions with every client connected to the
+/// websocket server at `url`.
+///
+/// This is [`use_tea_model_synced_with`] over a [`WebSocket`]; see there for how actions travel between clients.
+/// The url is only used on the first render.
+#[cfg(all(feature = "ws", not(target_arch = "wasm32")))]
+pub fn use_tea_model_synced<T>(url: &str) -> TeaModelSignal<T>
+where
+    T: TeaModel + TeaModelInit,
+    T::Action: Serialize + DeserializeOwned,
+{
+    let url = url.to_string();
+    use_tea_model_synced_with(move || WebSocket::new(url))
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, sharing its actions with other clients through the
+/// transport returned by `transport`.
+///
+/// Every action applied locally is serialized to JSON and sent out, including the actions produced by commands.
+/// Every action received is applied as soon as it arrives, ahead of the local actions that are still queued, but
+/// its commands are not run: the client the action originates from runs them, and shares the actions they produce
+/// in turn. Received actions are not sent back out, so they never echo.
+///
+/// Clients only share actions, not states, so they stay in sync only as long as they start from the same state
+/// and apply the same actions in the same order. Over the network, the order is only guaranteed per client: when
+/// two clients act simultaneously, each of them applies its own action first, and the states can drift apart. Keep
+/// shared actions commutative where possible, so the order in which they are applied does not matter, e.g. "add a
+/// cup" rather than "set the cups to 3".
+///
+/// When the connection closes or cannot be opened, it is reopened after a pause that doubles with every failed
+/// attempt, up to 30 seconds. Actions applied while disconnected are not sent, and actions sent by the others in
+/// the meantime are missed. Received messages that do not deserialize into an action are ignored. The connection
+/// is closed when the component that owns the model unmounts.
+pub fn use_tea_model_synced_with<T, R>(transport: impl FnOnce() -> R) -> TeaModelSignal<T>
+where
+    T: TeaModel + TeaModelInit,
+    T::Action: Serialize + DeserializeOwned,
+    R: TeaTransport,
+{
+    let (outgoing, remote) = use_hook(|| {
+        (
+            Rc::new(RefCell::new(None::<UnboundedSender<String>>)),
+            Rc::new(Cell::new(false)),
+        )
+    });
+
+    let model = use_tea_model_processed(T::init, {
+        let (outgoing, remote) = (outgoing.clone(), remote.clone());
+        move || {
+            Box::new(move |state: &mut T, error, action: T::Action| {
+                if remote.get() {
+                    let _ = apply(state, error, action);
+                    return Cmd::none();
+                }
+                if let (Some(outgoing), Ok(json)) = (&*outgoing.borrow(), serde_json::to_string(&action)) {
+                    let _ = outgoing.unbounded_send(json);
+                }
+                apply(state, error, action)
+            })
+        }
+    });
+
+    use_hook(|| {
+        let mut transport = transport();
+        model.spawn(async move {
+            let mut backoff = Duration::from_secs(1);
+            loop {
+                if let Some(mut connection) = transport.connect().await {
+                    backoff = Duration::from_secs(1);
+                    outgoing.replace(Some(connection.outgoing));
+                    while let Some(json) = connection.incoming.next().await {
+                        if let Ok(action) = serde_json::from_str(&json) {
+                            remote.set(true);
+                            model.send_sync(action);
+                            remote.set(false);
+                        }
+                    }
+                    outgoing.replace(None);
+                }
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        });
+    });
+
+    model
+}
+
+/// A [`TeaTransport`] over a plain `ws://` websocket, served by a background thread.
+///
+/// Encrypted `wss://` urls are not supported; implement [`TeaTransport`] with a TLS-enabled client for those. Since
+/// the connection runs on a native thread, this is not available on `wasm32-unknown-unknown`, where a transport
+/// around the browser's `WebSocket` is the way to go.
+#[cfg(all(feature = "ws", not(target_arch = "wasm32")))]
+pub struct WebSocket {
+    url: String,
+}
+
+#[cfg(all(feature = "ws", not(target_arch = "wasm32")))]
+impl WebSocket {
+    #[must_use]
+    /// Creates a transport that connects to the websocket server at `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+#[cfg(all(feature = "ws", not(target_arch = "wasm32")))]
+impl TeaTransport for WebSocket {
+    async fn connect(&mut self) -> Option<TeaConnection> {
+        use futures_channel::{mpsc, oneshot};
+        use tungstenite::{stream::MaybeTlsStream, Message};
+
+        let (connected_tx, connected_rx) = oneshot::channel();
+        let url = self.url.clone();
+        std::thread::Builder::new()
+            .name("tea-websocket".to_string())
+            .spawn(move || {
+                let Ok((mut socket, _)) = tungstenite::connect(url) else {
+                    return;
+                };
+                // reading times out regularly, so the thread gets to send the outgoing actions in between
+                if let MaybeTlsStream::Plain(stream) = socket.get_mut() {
+                    let _ = stream.set_read_timeout(Some(Duration::from_millis(20)));
+                }
+
+                let (outgoing_tx, mut outgoing) = mpsc::unbounded::<String>();
+                let (incoming, incoming_rx) = mpsc::unbounded();
+                if connected_tx.send((outgoing_tx, incoming_rx)).is_err() {
+                    return;
+                }
+
+                loop {
+                    loop {
+                        match outgoing.try_next() {
+                            Ok(Some(json)) => {
+                                if socket.send(Message::text(json)).is_err() {
+                                    return;
+                                }
+                            }
+                            // the model has been dropped
+                            Ok(None) => return,
+                            Err(_) => break,
+                        }
+                    }
+
+                    match socket.read() {
+                        Ok(Message::Text(json)) => {
+                            if incoming.unbounded_send(json.to_string()).is_err() {
+                                return;
+                            }
+                        }
+                        Ok(Message::Close(_)) => return,
+                        Ok(_) => {}
+                        Err(tungstenite::Error::Io(error))
+                            if matches!(
+                                error.kind(),
+                                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                            ) => {}
+                        Err(_) => return,
+                    }
+                }
+            })
+            .ok()?;
+
+        let (outgoing, incoming) = connected_rx.await.ok()?;
+        Some(TeaConnection::new(outgoing, incoming))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{mount_model, run};
+    use futures_channel::mpsc::{self, UnboundedReceiver};
+    use serde::Deserialize;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Table {
+        cups: u8,
+        served: u8,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    enum TableAction {
+        Pour,
+        Poured,
+    }
+
+    impl TeaModel for Table {
+        type Action = TableAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                TableAction::Pour => {
+                    self.cups += 1;
+                    return Cmd::perform(std::future::ready(()), |()| TableAction::Poured);
+                }
+                TableAction::Poured => self.served += 1,
+            }
+            Cmd::none()
+        }
+    }
+
+    thread_local! {
+        static LOOPBACK: RefCell<Option<(UnboundedSender<String>, UnboundedReceiver<String>)>> =
+            const { RefCell::new(None) };
+    }
+
+    /// Connects once, to channels that the test holds the other ends of.
+    struct Loopback(Option<TeaConnection>);
+
+    impl TeaTransport for Loopback {
+        async fn connect(&mut self) -> Option<TeaConnection> {
+            match self.0.take() {
+                Some(connection) => Some(connection),
+                None => std::future::pending().await,
+            }
+        }
+    }
+
+    fn use_table() -> TeaModelSignal<Table> {
+        use_tea_model_synced_with::<Table, _>(|| {
+            let (outgoing, sent) = mpsc::unbounded();
+            let (received, incoming) = mpsc::unbounded();
+            LOOPBACK.set(Some((received, sent)));
+            Loopback(Some(TeaConnection::new(outgoing, incoming)))
+        })
+    }
+
+    #[tokio::test]
+    async fn local_actions_are_sent_and_remote_ones_applied_without_echo() {
+        let (mut dom, table) = mount_model(use_table);
+        let (received, mut sent) = LOOPBACK.take().expect("the transport is created on the first render");
+        run(&mut dom).await;
+
+        dom.in_runtime(|| table.send(TableAction::Pour));
+        run(&mut dom).await;
+        let sent_json: Vec<_> = std::iter::from_fn(|| sent.try_next().ok().flatten()).collect();
+        assert_eq!(
+            sent_json,
+            [r#""Pour""#, r#""Poured""#],
+            "Local actions and their follow-ups are shared"
+        );
+
+        received.unbounded_send(r#""Pour""#.to_string()).unwrap();
+        received.unbounded_send(r#""Poured""#.to_string()).unwrap();
+        run(&mut dom).await;
+        assert_eq!(dom.in_runtime(|| table.peek().clone()), Table { cups: 2, served: 2 });
+        assert!(
+            sent.try_next().is_err(),
+            "Remote actions are not echoed, nor are their commands run"
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Helpers for tests that need a running virtual dom.
+
+use dioxus::{dioxus_core::NoOpMutations, prelude::*};
+use std::time::Duration;
+
+/// Builds a virtual dom for `app` and returns it together with the context of type `C` provided by `app`.
+pub(crate) fn mount<C: Clone + 'static>(app: fn() -> Element) -> (VirtualDom, C) {
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    let context = dom.in_scope(ScopeId::APP, consume_context::<C>);
+    (dom, context)
+}
+
+/// Builds a virtual dom whose app creates a model with `hook` and provides it as context, and returns it together
+/// with the model, for the many tests that need nothing but a mounted model.
+pub(crate) fn mount_model<M: Clone + 'static>(hook: fn() -> M) -> (VirtualDom, M) {
+    fn app<M: Clone + 'static>(hook: fn() -> M) -> Element {
+        let model = hook();
+        use_context_provider(|| model);
+        rsx! {}
+    }
+
+    let mut dom = VirtualDom::new_with_props(app::<M>, hook);
+    dom.rebuild_in_place();
+    let model = dom.in_scope(ScopeId::APP, consume_context::<M>);
+    (dom, model)
+}
+
+/// Renders and polls the virtual dom until no more work shows up for a little while.
+pub(crate) async fn run(dom: &mut VirtualDom) {
+    loop {
+        tokio::select! {
+            () = dom.wait_for_work() => dom.render_immediate(&mut NoOpMutations),
+            () = tokio::time::sleep(Duration::from_millis(50)) => break,
+        }
+    }
+}
+
+/// Renders and polls the virtual dom for `duration`, for models that keep producing work, like intervals.
+pub(crate) async fn run_for(dom: &mut VirtualDom, duration: Duration) {
+    let deadline = tokio::time::Instant::now() + duration;
+    while tokio::time::timeout_at(deadline, dom.wait_for_work()).await.is_ok() {
+        dom.render_immediate(&mut NoOpMutations);
+    }
+}
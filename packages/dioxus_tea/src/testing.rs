@@ -0,0 +1,206 @@
+//! Testing the reducers of TEA models without a Dioxus runtime.
+
+use crate::{process_chain, TeaModel, TeaModelInit};
+use futures_util::future::LocalBoxFuture;
+use std::{collections::VecDeque, fmt::Debug};
+
+/// Drives a [`TeaModel`] synchronously, for exhaustive tests of its reducer.
+///
+/// The harness owns the state and applies actions to it directly, running their commands to completion with a
+/// local executor before returning. That makes every test deterministic and free of sleeps, but it only covers the
+/// semantics of `update`: no signal, coroutine or component is involved, so queueing, re-renders and the hooks of
+/// this crate are not exercised.
+///
+/// ```rust
+/// use dioxus_tea::{testing::TeaTestHarness, Cmd, TeaModel};
+///
+/// #[derive(Default, Clone, PartialEq, Debug)]
+/// struct Cup {
+///     sugar: u8,
+/// }
+///
+/// impl TeaModel for Cup {
+///     type Action = u8;
+///
+///     fn update(&mut self, sugar: Self::Action) -> Cmd<Self::Action> {
+///         self.sugar += sugar;
+///         Cmd::none()
+///     }
+/// }
+///
+/// let mut cup = TeaTestHarness::<Cup>::init();
+/// cup.dispatch(1);
+/// cup.dispatch(2);
+/// cup.assert_state(|cup| cup.sugar == 3);
+/// ```
+pub struct TeaTestHarness<T: TeaModel> {
+    state: T,
+    /// Effects of the applied actions that have not been run yet.
+    effects: VecDeque<LocalBoxFuture<'static, Option<T::Action>>>,
+    /// The actions produced by effects, in the order they were applied.
+    effect_actions: Vec<T::Action>,
+}
+
+impl<T: TeaModel> TeaTestHarness<T> {
+    #[must_use]
+    /// Creates a harness starting from `state`.
+    pub fn new(state: T) -> Self {
+        Self {
+            state,
+            effects: VecDeque::new(),
+            effect_actions: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    /// Creates a harness starting from the initial state of the model, see [`TeaModelInit`].
+    pub fn init() -> Self
+    where
+        T: TeaModelInit,
+    {
+        Self::new(T::init())
+    }
+
+    #[must_use]
+    /// Returns the current state.
+    pub fn state(&self) -> &T {
+        &self.state
+    }
+
+    /// Applies `action`, then runs its effects and applies the actions they produce, until no effect is left.
+    ///
+    /// Effects run one at a time, in the order they were returned, and each of them is awaited to completion before
+    /// the next one starts, so their actions are applied in a fixed order. The effects must be able to make progress
+    /// without a runtime, e.g. they must not await the timers of tokio or of the browser; use
+    /// [`apply`](Self::apply) for reducers with such effects.
+    pub fn dispatch(&mut self, action: T::Action) -> &T
+    where
+        T::Action: Clone,
+    {
+        self.apply(action);
+        self.run_effects()
+    }
+
+    /// Applies `action` without running its effects, which stay pending until [`run_effects`](Self::run_effects).
+    ///
+    /// Like in the model's coroutine, an action for which [`TeaModel::cancels_effects`] returns `true` drops the
+    /// effects that are still pending, and the follow-ups of [`Cmd::dispatch_next`](crate::Cmd::dispatch_next) are
+    /// applied right away.
+    pub fn apply(&mut self, action: T::Action) -> &T {
+        let effects = &mut self.effects;
+        let cmd = process_chain(
+            &mut self.state,
+            action,
+            |state, action| Some(state.try_apply(action).unwrap_or_default()),
+            || effects.clear(),
+        );
+        self.effects.extend(cmd.unwrap_or_default().into_effects());
+        &self.state
+    }
+
+    /// Runs the pending effects and applies the actions they produce, until no effect is left; see
+    /// [`dispatch`](Self::dispatch).
+    pub fn run_effects(&mut self) -> &T
+    where
+        T::Action: Clone,
+    {
+        while let Some(effect) = self.effects.pop_front() {
+            if let Some(action) = futures_executor::block_on(effect) {
+                self.effect_actions.push(action.clone());
+                self.apply(action);
+            }
+        }
+        &self.state
+    }
+
+    #[must_use]
+    /// Returns the number of effects that have not been run yet.
+    pub fn pending_effects(&self) -> usize {
+        self.effects.len()
+    }
+
+    #[must_use]
+    /// Returns every action produced by an effect so far, in the order they were applied.
+    pub fn effect_actions(&self) -> &[T::Action] {
+        &self.effect_actions
+    }
+
+    #[track_caller]
+    /// Asserts that `predicate` holds for the current state.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the state when the predicate does not hold.
+    pub fn assert_state(&self, predicate: impl FnOnce(&T) -> bool)
+    where
+        T: Debug,
+    {
+        assert!(predicate(&self.state), "unexpected state: {:?}", self.state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cmd;
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Kettle {
+        temperature: u8,
+        whistled: bool,
+    }
+
+    #[derive(Clone, PartialEq, Debug)]
+    enum KettleAction {
+        Heat(u8),
+        Heated(u8),
+        Whistle,
+        Unplug,
+    }
+
+    impl TeaModel for Kettle {
+        type Action = KettleAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                KettleAction::Heat(temperature) => {
+                    return Cmd::perform(std::future::ready(temperature), KettleAction::Heated);
+                }
+                KettleAction::Heated(temperature) => {
+                    self.temperature = temperature;
+                    return Cmd::perform(std::future::ready(()), |()| KettleAction::Whistle);
+                }
+                KettleAction::Whistle => self.whistled = true,
+                KettleAction::Unplug => {}
+            }
+            Cmd::none()
+        }
+
+        fn cancels_effects(action: &Self::Action) -> bool {
+            matches!(action, KettleAction::Unplug)
+        }
+    }
+
+    #[test]
+    fn dispatching_drains_the_follow_up_actions() {
+        let mut kettle = TeaTestHarness::<Kettle>::init();
+        kettle.dispatch(KettleAction::Heat(100));
+
+        kettle.assert_state(|kettle| kettle.temperature == 100 && kettle.whistled);
+        assert_eq!(
+            kettle.effect_actions(),
+            [KettleAction::Heated(100), KettleAction::Whistle]
+        );
+    }
+
+    #[test]
+    fn applying_keeps_the_effects_pending_until_cancelled() {
+        let mut kettle = TeaTestHarness::<Kettle>::init();
+        kettle.apply(KettleAction::Heat(100));
+        assert_eq!(kettle.pending_effects(), 1);
+
+        kettle.apply(KettleAction::Unplug);
+        kettle.run_effects();
+        assert_eq!(*kettle.state(), Kettle::default());
+    }
+}
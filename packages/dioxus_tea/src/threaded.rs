@@ -0,0 +1,255 @@
+//! TEA models whose updates run on a background thread or another executor, for reducers that do CPU-heavy work.
+
+use crate::{new_tea_model, process_action, Cmd, Processor, Queue, TeaModel, TeaModelInit, TeaModelSignal};
+use dioxus::{dioxus_core::current_scope_id, prelude::use_hook};
+use futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures_util::{
+    future::{self, Either, LocalBoxFuture},
+    stream::FuturesUnordered,
+    FutureExt, StreamExt,
+};
+
+/// Creates the future that applies the actions of a model created with [`use_tea_model_on`], on the executor that
+/// runs it.
+pub type TeaWorker = Box<dyn FnOnce() -> LocalBoxFuture<'static, ()> + Send>;
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, running `update` on a background thread.
+///
+/// The thread owns its own copy of the state. Every action passed to [`TeaModelSignal::send`] is forwarded to it, and
+/// whenever an update changed the state, the new state is sent back and written to the signal on the main thread, like
+/// any other change: it bumps the [`version`](TeaModelSignal::version) and reaches
+/// [`changes`](TeaModelSignal::changes). This keeps expensive updates from blocking the UI, at the cost of some
+/// latency: the state is not updated when the coroutine picks up an action, but only once the thread has applied it and
+/// the main thread received the result. That is also why both the state and its actions must be `Send`, unlike with
+/// [`use_tea_model`](crate::use_tea_model).
+///
+/// Commands returned by `update` run on the background thread as well, and their actions are applied there
+/// directly; they are not cancelled by [`TeaModel::cancels_effects`]. Errors of a
+/// [`TryTeaModel`](crate::TryTeaModel) are discarded, and [`reset`](TeaModelSignal::reset) only resets the state on
+/// the main thread.
+///
+/// The thread stops when the component that owns the model unmounts. Since it is a native thread, this is not
+/// available on `wasm32-unknown-unknown`.
+///
+/// # Panics
+///
+/// Panics if the background thread cannot be spawned.
+pub fn use_tea_model_threaded<T>() -> TeaModelSignal<T>
+where
+    T: TeaModel + TeaModelInit + Send,
+    T::Action: Send,
+{
+    use_tea_model_on(|worker: TeaWorker| {
+        std::thread::Builder::new()
+            .name("tea-model".to_string())
+            .spawn(move || futures_executor::block_on(worker()))
+            .expect("failed to spawn the thread of a threaded TEA model");
+    })
+}
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel`, running `update` and its commands on the executor that
+/// `spawner` hands the [`TeaWorker`] to, e.g. a dedicated tokio runtime on desktop.
+///
+/// This works like [`use_tea_model_threaded`], which is this hook with a plain thread as the executor: the worker
+/// owns its own copy of the state, applies the forwarded actions to it, and sends every changed state back to the
+/// signal. Commands are not `Send`, so the worker cannot be a `Send` future itself; instead, `spawner` gets a `Send`
+/// closure that creates the worker on the thread that will poll it. Only the state and its actions cross threads,
+/// which is why both must be `Send`. The future must be polled from a single thread, with the context that the
+/// commands need, e.g. a tokio runtime for tokio's timers:
+///
+/// ```rust,ignore
+/// let handle = runtime.handle().clone();
+/// let model = use_tea_model_on::<AppState>(move |worker| {
+///     let runtime = handle.clone();
+///     handle.spawn_blocking(move || runtime.block_on(worker()));
+/// });
+/// ```
+///
+/// The worker returns when the component that owns the model unmounts. `spawner` is only called on the first
+/// render; the default [`use_tea_model`](crate::use_tea_model) keeps processing actions on the Dioxus runtime.
+pub fn use_tea_model_on<T>(spawner: impl FnOnce(TeaWorker)) -> TeaModelSignal<T>
+where
+    T: TeaModel + TeaModelInit + Send,
+    T::Action: Send,
+{
+    use_hook(|| {
+        let (actions_tx, actions_rx) = mpsc::unbounded();
+        let (states_tx, mut states_rx) = mpsc::unbounded();
+        let initial = T::init();
+        let worker_state = initial.clone();
+        spawner(Box::new(move || {
+            work(worker_state, actions_rx, states_tx).boxed_local()
+        }));
+
+        let processor: Processor<T> = Box::new(move |_state, _error, action| {
+            let _ = actions_tx.unbounded_send(action);
+            Cmd::none()
+        });
+        let model = new_tea_model(current_scope_id(), initial, processor, Queue::Unbounded);
+
+        model.spawn(async move {
+            while let Some(state) = states_rx.next().await {
+                model.commit(state);
+            }
+        });
+        model
+    })
+}
+
+/// Applies the actions to `state` on the worker's executor, sending every changed state to `states`.
+///
+/// Returns once the model has been dropped on the main thread.
+async fn work<T: TeaModel>(mut state: T, mut actions: UnboundedReceiver<T::Action>, states: UnboundedSender<T>) {
+    // the pending future keeps the set of effects from ever running dry, so it can be selected on at all times
+    let mut effects = FuturesUnordered::<LocalBoxFuture<'static, Option<T::Action>>>::new();
+    effects.push(future::pending().boxed_local());
+
+    loop {
+        let action = match future::select(actions.next(), effects.next()).await {
+            Either::Left((Some(action), _)) | Either::Right((Some(Some(action)), _)) => action,
+            Either::Right((Some(None), _)) => continue,
+            Either::Left((None, _)) | Either::Right((None, _)) => return,
+        };
+
+        let previous = state.clone();
+        effects.extend(process_action(&mut state, action).into_effects());
+        if T::changed(&previous, &state) && states.unbounded_send(state.clone()).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{mount_model, run};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Thumbnail {
+        pixels: u32,
+        rendered_on: Option<String>,
+    }
+
+    enum ThumbnailAction {
+        Resize(u32),
+        Rendered,
+    }
+
+    impl TeaModel for Thumbnail {
+        type Action = ThumbnailAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                ThumbnailAction::Resize(size) => {
+                    self.pixels = size * size;
+                    return Cmd::perform(std::future::ready(()), |()| ThumbnailAction::Rendered);
+                }
+                ThumbnailAction::Rendered => {
+                    self.rendered_on = std::thread::current().name().map(ToString::to_string);
+                }
+            }
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn updates_run_on_the_background_thread() {
+        let (mut dom, thumbnail) = mount_model(use_tea_model_threaded::<Thumbnail>);
+        let resized = dom.in_runtime(|| {
+            let resized = thumbnail.next_change();
+            thumbnail.send(ThumbnailAction::Resize(4));
+            thumbnail.send(ThumbnailAction::Resize(8));
+            resized
+        });
+        run(&mut dom).await;
+
+        assert_eq!(resized.now_or_never().map(|resized| resized.pixels), Some(16));
+        assert!(dom.in_runtime(|| thumbnail.last_changed()));
+        let thumbnail = dom.in_runtime(|| thumbnail.peek().clone());
+        assert_eq!(thumbnail.pixels, 64);
+        assert_eq!(thumbnail.rendered_on.as_deref(), Some("tea-model"));
+    }
+
+    /// Numbers every order it creates, so creating the state twice would hand out two numbers.
+    static ORDERS: AtomicU32 = AtomicU32::new(0);
+
+    #[derive(Clone, PartialEq, Debug)]
+    struct Order {
+        number: u32,
+        served: bool,
+    }
+
+    impl TeaModelInit for Order {
+        fn init() -> Self {
+            Order {
+                number: ORDERS.fetch_add(1, Ordering::Relaxed),
+                served: false,
+            }
+        }
+    }
+
+    impl TeaModel for Order {
+        type Action = ();
+
+        fn update(&mut self, (): Self::Action) -> Cmd<Self::Action> {
+            self.served = true;
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn the_worker_starts_from_the_same_state() {
+        let (mut dom, order) = mount_model(use_tea_model_threaded::<Order>);
+        let number = dom.in_runtime(|| {
+            order.send(());
+            order.peek().number
+        });
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| order.peek().clone()), Order { number, served: true });
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Brew {
+        steeped_on: Option<String>,
+    }
+
+    impl TeaModel for Brew {
+        type Action = bool;
+
+        fn update(&mut self, steeped: Self::Action) -> Cmd<Self::Action> {
+            if steeped {
+                self.steeped_on = std::thread::current().name().map(ToString::to_string);
+                return Cmd::none();
+            }
+            // tokio's timers panic outside of a tokio runtime
+            Cmd::perform(tokio::time::sleep(std::time::Duration::from_millis(1)), |()| true)
+        }
+    }
+
+    #[tokio::test]
+    async fn commands_run_on_the_executor_of_the_spawner() {
+        let (mut dom, brew) = mount_model(|| {
+            use_tea_model_on::<Brew>(|worker| {
+                std::thread::Builder::new()
+                    .name("tea-runtime".to_string())
+                    .spawn(move || {
+                        let runtime = tokio::runtime::Builder::new_current_thread()
+                            .enable_time()
+                            .build()
+                            .unwrap();
+                        runtime.block_on(worker());
+                    })
+                    .unwrap();
+            })
+        });
+        dom.in_runtime(|| brew.send(false));
+        run(&mut dom).await;
+
+        let steeped_on = dom.in_runtime(|| brew.peek().steeped_on.clone());
+        assert_eq!(steeped_on.as_deref(), Some("tea-runtime"));
+    }
+}
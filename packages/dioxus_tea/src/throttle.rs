@@ -0,0 +1,110 @@
+//! Throttled dispatching of actions.
+
+use crate::{TeaModel, TeaModelSignal};
+use dioxus::prelude::WritableExt;
+use std::time::Duration;
+
+/// State of [`TeaModelSignal::send_throttled`].
+pub(crate) struct Throttle<A> {
+    /// Whether an interval is currently running.
+    active: bool,
+    /// The most recent action that was suppressed during the running interval.
+    trailing: Option<A>,
+}
+
+impl<A> Default for Throttle<A> {
+    fn default() -> Self {
+        Self {
+            active: false,
+            trailing: None,
+        }
+    }
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Sends at most one action per `interval`, delivering both the leading and the trailing action.
+    ///
+    /// - Leading edge: when no interval is running, the action is sent right away and an interval starts.
+    /// - While the interval runs, actions are suppressed; only the most recent one is kept.
+    /// - Trailing edge: when the interval ends, the kept action (if any) is sent and a new interval starts, so
+    ///   actions are never delivered more often than once per `interval`. Without a kept action, the throttle goes
+    ///   idle and the next action is a leading one again.
+    ///
+    /// Throttled actions are delivered with [`send`](Self::send), so they are ordered with all other queued actions.
+    /// The length of the running interval is set by the action that started it.
+    pub fn send_throttled(&self, action: T::Action, interval: Duration) {
+        let mut throttle = self.throttle;
+        {
+            let mut throttle = throttle.write();
+            if throttle.active {
+                throttle.trailing = Some(action);
+                return;
+            }
+            throttle.active = true;
+        }
+
+        self.send(action);
+
+        let model = *self;
+        let clock = self.clock();
+        self.spawn(async move {
+            loop {
+                clock.sleep(interval).await;
+                let trailing = {
+                    let mut throttle = throttle.write();
+                    let trailing = throttle.trailing.take();
+                    throttle.active = trailing.is_some();
+                    trailing
+                };
+                match trailing {
+                    Some(action) => model.send(action),
+                    None => break,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model, Cmd,
+    };
+
+    #[derive(Default, Clone, PartialEq)]
+    struct Scroll {
+        positions: Vec<u32>,
+    }
+
+    impl TeaModel for Scroll {
+        type Action = u32;
+
+        fn update(&mut self, position: Self::Action) -> Cmd<Self::Action> {
+            self.positions.push(position);
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn leading_and_trailing_actions_are_delivered() {
+        let (mut dom, scroll) = mount_model(use_tea_model::<Scroll>);
+        dom.in_runtime(|| {
+            for position in [10, 20, 30] {
+                scroll.send_throttled(position, Duration::from_millis(10));
+            }
+        });
+        run(&mut dom).await;
+
+        assert_eq!(dom.in_runtime(|| scroll.peek().positions.clone()), [10, 30]);
+
+        dom.in_runtime(|| scroll.send_throttled(40, Duration::from_millis(10)));
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| scroll.peek().positions.clone()),
+            [10, 30, 40],
+            "An idle throttle delivers the next action right away"
+        );
+    }
+}
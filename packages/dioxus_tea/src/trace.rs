@@ -0,0 +1,152 @@
+//! Tracing instrumentation of TEA models.
+
+use crate::{apply, source, use_tea_model_processed, TeaModel, TeaModelInit, TeaModelSignal};
+use std::fmt::Debug;
+use tracing::{debug, debug_span};
+use web_time::Instant;
+
+#[must_use]
+/// Creates a new `TeaModelSignal` for the given `TeaModel` that traces every action.
+///
+/// Each action is processed inside a `tea_update` debug span that records the action and the source it was sent
+/// from, see [`send_from`](TeaModelSignal::send_from). Once `update` returns, a debug event reports whether the state
+/// changed, the resulting state and how long the update took (`elapsed`).
+pub fn use_tea_model_traced<T>() -> TeaModelSignal<T>
+where
+    T: TeaModel + TeaModelInit + Debug,
+    T::Action: Debug,
+{
+    use_tea_model_processed(T::init, || {
+        Box::new(|state: &mut T, error, action| {
+            let span = debug_span!(
+                "tea_update",
+                model = std::any::type_name::<T>(),
+                action = ?action,
+                source = source::current()
+            );
+            let _entered = span.enter();
+
+            let previous = state.clone();
+            let start = Instant::now();
+            let cmd = apply(state, error, action);
+            let elapsed = start.elapsed();
+
+            debug!(
+                changed = T::changed(&previous, state),
+                ?state,
+                ?elapsed,
+                "applied action"
+            );
+            cmd
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::mount_model, Cmd};
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id},
+        Event, Subscriber,
+    };
+    use tracing_subscriber::{
+        layer::{Context, SubscriberExt},
+        registry::LookupSpan,
+        Layer,
+    };
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Mug {
+        sips: u8,
+    }
+
+    impl TeaModel for Mug {
+        type Action = u8;
+
+        fn update(&mut self, sips: Self::Action) -> Cmd<Self::Action> {
+            self.sips += sips;
+            Cmd::none()
+        }
+    }
+
+    /// The fields of a span or an event, rendered with `Debug`.
+    type Fields = BTreeMap<&'static str, String>;
+
+    /// Collects the fields of every event, together with the ones of the span it was emitted in.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<Fields>>>);
+
+    struct Recorder<'a>(&'a mut Fields);
+
+    impl Visit for Recorder<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.insert(field.name(), format!("{value:?}"));
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Capture {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let mut fields = Fields::from([("span", attrs.metadata().name().to_string())]);
+            attrs.record(&mut Recorder(&mut fields));
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(fields);
+            }
+        }
+
+        fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+            let mut fields = ctx
+                .event_span(event)
+                .and_then(|span| span.extensions().get::<Fields>().cloned())
+                .unwrap_or_default();
+            event.record(&mut Recorder(&mut fields));
+            self.0.lock().unwrap().push(fields);
+        }
+    }
+
+    #[test]
+    fn every_action_is_traced_in_a_span_of_its_own() {
+        let capture = Capture::default();
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(capture.clone()), || {
+            let (dom, mug) = mount_model(use_tea_model_traced::<Mug>);
+            dom.in_runtime(|| {
+                mug.send_sync(2);
+                mug.send_sync(0);
+            });
+        });
+
+        let events = capture.0.lock().unwrap();
+        let updates: Vec<_> = events
+            .iter()
+            // dioxus emits events of its own while the action is applied
+            .filter(|fields| fields.get("message").is_some_and(|message| message == "applied action"))
+            .collect();
+        assert_eq!(updates.len(), 2, "Every action is traced");
+        assert!(updates.iter().all(|fields| fields["span"] == "tea_update"));
+        assert_eq!(
+            (updates[0]["action"].as_str(), updates[0]["changed"].as_str()),
+            ("2", "true")
+        );
+        assert_eq!(
+            (updates[1]["action"].as_str(), updates[1]["changed"].as_str()),
+            ("0", "false"),
+            "Sipping nothing leaves the mug as it is"
+        );
+        assert!(updates.iter().all(|fields| fields.contains_key("elapsed")));
+    }
+
+    #[test]
+    fn traced_models_apply_actions() {
+        let (dom, mug) = mount_model(use_tea_model_traced::<Mug>);
+        dom.in_runtime(|| {
+            mug.send_sync(2);
+            mug.send_sync(1);
+            assert_eq!(mug.peek().sips, 3);
+        });
+    }
+}
@@ -0,0 +1,210 @@
+//! Transactions: several actions that are applied together, or not at all.
+
+use crate::{notify, process_chain, Cmd, Envelope, TeaModel, TeaModelSignal};
+use dioxus::{
+    dioxus_core::ScopeId,
+    prelude::{ReadableExt, WritableExt},
+};
+use std::panic::AssertUnwindSafe;
+
+/// The result of a transaction that is about to be committed.
+struct Transaction<T: TeaModel> {
+    state: T,
+    cmd: Cmd<T::Action>,
+    /// The notifications of its updates.
+    emitted: Vec<String>,
+    /// Whether one of its actions [cancels effects](TeaModel::cancels_effects).
+    cancels: bool,
+}
+
+impl<T: TeaModel> TeaModelSignal<T> {
+    /// Sends several actions that are applied together, keeping their result only if `validate` accepts it.
+    ///
+    /// Like a [batch](Self::send_batch), the transaction keeps its place in the queue. When its turn comes, the
+    /// actions, and the follow-ups they [dispatch](Cmd::dispatch_next), are applied to a copy of the state. If every
+    /// update succeeded and `validate` returns `true` for the resulting state, the transaction is committed: the state
+    /// is written once, its notifications are shown, and its commands are spawned. Otherwise, it is discarded as a
+    /// whole, and nothing happens: the state and the error sink are left as they were, the commands are dropped
+    /// without running, and the notifications are never shown. An update of a [`TryTeaModel`](crate::TryTeaModel)
+    /// that fails, or an update that panics, aborts the transaction as well. An action that
+    /// [cancels effects](TeaModel::cancels_effects) drops the commands of the actions before it, and cancels the
+    /// running commands once the transaction is committed.
+    ///
+    /// ```rust,ignore
+    /// // brewing a pot takes both the tea and the water, or neither
+    /// pantry.transaction(vec![PantryAction::TakeTea(4), PantryAction::TakeWater(1000)], |pantry| {
+    ///     pantry.tea >= 0 && pantry.water >= 0
+    /// });
+    /// ```
+    ///
+    /// The actions go straight to `update`, so they bypass the middleware, the rules of a
+    /// [`TeaStateMachine`](crate::TeaStateMachine), and the observers of the actions, like
+    /// [`action_stream`](Self::action_stream). Rolling back is only sound for a pure reducer: whatever `update` does
+    /// outside of the state and its commands, like writing to another model or to storage, is not undone when the
+    /// transaction is discarded.
+    pub fn transaction(&self, actions: Vec<T::Action>, validate: impl Fn(&T) -> bool + 'static) {
+        let model = *self;
+        let commit = move |scope: ScopeId, actions: Vec<T::Action>| {
+            if let Some(transaction) = model.try_transaction(actions, &validate) {
+                let (mut error, mut running) = (model.error, model.running);
+                if error.peek().is_some() {
+                    error.set(None);
+                }
+                if transaction.cancels {
+                    running.write().cancel_all();
+                }
+                model.commit(transaction.state);
+                model.notify(&transaction.emitted);
+                model.spawn_cmd(scope, transaction.cmd);
+            }
+        };
+        let _ = self.enqueue(Envelope::Transaction(actions, Box::new(commit)));
+    }
+
+    /// Applies the actions of a transaction to a copy of the state, returning the result if every update succeeded
+    /// and the state is valid.
+    fn try_transaction(&self, actions: Vec<T::Action>, validate: &impl Fn(&T) -> bool) -> Option<Transaction<T>> {
+        let mut state = self.inner.peek().clone();
+        let (applied, emitted) = notify::collect(|| {
+            std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let (mut cmds, mut cancels) = (Vec::new(), false);
+                for action in actions {
+                    // an action that cancels effects also drops the commands of the actions before it
+                    let cmd = process_chain(
+                        &mut state,
+                        action,
+                        |state, action| state.try_apply(action).ok(),
+                        || {
+                            cmds.clear();
+                            cancels = true;
+                        },
+                    )?;
+                    cmds.push(cmd);
+                }
+                Some((Cmd::batch(cmds), cancels))
+            }))
+        });
+        let (cmd, cancels) = applied.ok().flatten().filter(|_| validate(&state))?;
+        Some(Transaction {
+            state,
+            cmd,
+            emitted,
+            cancels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{mount_model, run},
+        use_tea_model,
+    };
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Pantry {
+        tea: i32,
+        water: i32,
+    }
+
+    enum PantryAction {
+        Stock(i32, i32),
+        TakeTea(i32),
+        TakeWater(i32),
+    }
+
+    impl TeaModel for Pantry {
+        type Action = PantryAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                PantryAction::Stock(tea, water) => {
+                    self.tea += tea;
+                    self.water += water;
+                }
+                PantryAction::TakeTea(tea) => self.tea -= tea,
+                PantryAction::TakeWater(water) => self.water -= water,
+            }
+            Cmd::none()
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_transactions_leave_the_state_untouched() {
+        let (mut dom, pantry) = mount_model(use_tea_model::<Pantry>);
+        let brew = || vec![PantryAction::TakeTea(4), PantryAction::TakeWater(1000)];
+        let in_stock = |pantry: &Pantry| pantry.tea >= 0 && pantry.water >= 0;
+        dom.in_runtime(|| {
+            pantry.send(PantryAction::Stock(10, 500));
+            pantry.transaction(brew(), in_stock);
+        });
+        run(&mut dom).await;
+        let version = dom.in_runtime(|| {
+            assert_eq!(
+                *pantry.peek(),
+                Pantry { tea: 10, water: 500 },
+                "There is not enough water"
+            );
+            pantry.version()
+        });
+
+        dom.in_runtime(|| {
+            pantry.send(PantryAction::Stock(0, 500));
+            pantry.transaction(brew(), in_stock);
+        });
+        run(&mut dom).await;
+        dom.in_runtime(|| {
+            assert_eq!(*pantry.peek(), Pantry { tea: 6, water: 0 });
+            assert_eq!(pantry.version(), version + 2, "The transaction is written once");
+        });
+    }
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    struct Kettle {
+        boiled: u8,
+    }
+
+    #[derive(PartialEq)]
+    enum KettleAction {
+        Boil,
+        Boiled,
+        Empty,
+    }
+
+    impl TeaModel for Kettle {
+        type Action = KettleAction;
+
+        fn update(&mut self, action: Self::Action) -> Cmd<Self::Action> {
+            match action {
+                KettleAction::Boil => {
+                    return Cmd::perform(tokio::time::sleep(std::time::Duration::from_millis(10)), |()| {
+                        KettleAction::Boiled
+                    });
+                }
+                KettleAction::Boiled => self.boiled += 1,
+                KettleAction::Empty => self.boiled = 0,
+            }
+            Cmd::none()
+        }
+
+        fn cancels_effects(action: &Self::Action) -> bool {
+            *action == KettleAction::Empty
+        }
+    }
+
+    #[tokio::test]
+    async fn transactions_cancel_effects_like_single_actions() {
+        let (mut dom, kettle) = mount_model(use_tea_model::<Kettle>);
+        dom.in_runtime(|| {
+            kettle.send(KettleAction::Boil);
+            kettle.transaction(vec![KettleAction::Boil, KettleAction::Empty], |_| true);
+        });
+        run(&mut dom).await;
+        assert_eq!(
+            dom.in_runtime(|| kettle.peek().boiled),
+            0,
+            "Neither the running nor the transaction's own boiling finishes"
+        );
+    }
+}
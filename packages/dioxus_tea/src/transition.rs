@@ -0,0 +1,142 @@
+//! Helpers for the guarded transitions that reducers write by hand, like "a tea bag can only go into an empty cup".
+
+/// Returns the state that follows `current`: the one from `on_match` if `current` is the `expected` state, and the
+/// one from `on_mismatch` otherwise.
+///
+/// This is the smallest form of a guard; for guards with more than one rejecting branch, or that route their
+/// rejections as errors, see [`tea_transition!`](crate::tea_transition).
+///
+/// ```rust,ignore
+/// self.status = transition(
+///     &self.status,
+///     &Status::EmptyCup,
+///     || Status::TeaBag(tea_type.clone()),
+///     || Status::Error(MakeTeaError::CupNotEmpty),
+/// );
+/// ```
+pub fn transition<S: PartialEq>(
+    current: &S,
+    expected: &S,
+    on_match: impl FnOnce() -> S,
+    on_mismatch: impl FnOnce() -> S,
+) -> S {
+    if current == expected {
+        on_match()
+    } else {
+        on_mismatch()
+    }
+}
+
+/// Matches a state against guarded arms, and returns the next state as `Ok`, or the error of the arm that rejected
+/// the state as `Err`.
+///
+/// The arms are those of a `match`, guards included, separated by commas: an arm whose value is written as
+/// `Err(error)` rejects the states it matches, while every other arm returns the next state. Like with a `match`,
+/// the arms must cover every state, and the state is matched in place, so pass a reference when patterns bind parts
+/// of it.
+///
+/// Since it returns a `Result`, the rejections compose with both kinds of reducers. In a
+/// [`TryTeaModel`](crate::TryTeaModel), `?` routes them to the error sink of the model; a plain
+/// [`TeaModel`](crate::TeaModel) maps them into its state instead, which is the place to call
+/// [`on_rejected`](crate::TeaModel::on_rejected) as well:
+///
+/// ```rust
+/// # use dioxus_tea::tea_transition;
+/// #[derive(PartialEq, Debug)]
+/// enum Status {
+///     EmptyCup,
+///     TeaBag,
+///     Error(MakeTeaError),
+/// }
+///
+/// #[derive(PartialEq, Debug)]
+/// enum MakeTeaError {
+///     CupNotEmpty,
+/// }
+///
+/// fn add_tea_bag(status: &Status) -> Status {
+///     tea_transition!(status, {
+///         Status::EmptyCup => Status::TeaBag,
+///         _ => Err(MakeTeaError::CupNotEmpty),
+///     })
+///     .unwrap_or_else(Status::Error)
+/// }
+///
+/// assert_eq!(add_tea_bag(&Status::EmptyCup), Status::TeaBag);
+/// assert_eq!(add_tea_bag(&Status::TeaBag), Status::Error(MakeTeaError::CupNotEmpty));
+/// ```
+#[macro_export]
+macro_rules! tea_transition {
+    (@arms ($current:expr) [$($done:tt)*]) => {
+        match $current {
+            $($done)*
+        }
+    };
+    (@arms ($current:expr) [$($done:tt)*] $from:pat $(if $guard:expr)? => Err($error:expr) $(, $($rest:tt)*)?) => {
+        $crate::tea_transition!(
+            @arms ($current) [$($done)* $from $(if $guard)? => ::core::result::Result::Err($error),] $($($rest)*)?
+        )
+    };
+    (@arms ($current:expr) [$($done:tt)*] $from:pat $(if $guard:expr)? => $to:expr $(, $($rest:tt)*)?) => {
+        $crate::tea_transition!(
+            @arms ($current) [$($done)* $from $(if $guard)? => ::core::result::Result::Ok($to),] $($($rest)*)?
+        )
+    };
+    ($current:expr, { $($arms:tt)* }) => {
+        $crate::tea_transition!(@arms ($current) [] $($arms)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cmd, TryTeaModel};
+
+    #[derive(Default, Clone, PartialEq, Debug)]
+    enum Pot {
+        #[default]
+        Empty,
+        Filled(u8),
+        Brewing(u8),
+    }
+
+    #[derive(PartialEq, Debug)]
+    enum PotError {
+        AlreadyFilled,
+        TooFewCups,
+    }
+
+    impl TryTeaModel for Pot {
+        type Action = u8;
+        type Error = PotError;
+
+        fn try_update(&mut self, cups: Self::Action) -> Result<Cmd<Self::Action>, Self::Error> {
+            *self = tea_transition!(*self, {
+                Pot::Empty if cups == 0 => Err(PotError::TooFewCups),
+                Pot::Empty => Pot::Filled(cups),
+                Pot::Filled(filled) => Pot::Brewing(filled + cups),
+                Pot::Brewing(_) => Err(PotError::AlreadyFilled),
+            })?;
+            Ok(Cmd::none())
+        }
+    }
+
+    #[test]
+    fn rejecting_arms_are_routed_as_errors() {
+        let mut pot = Pot::Empty;
+        assert_eq!(pot.try_update(0).err(), Some(PotError::TooFewCups));
+        assert_eq!(pot, Pot::Empty, "A rejected transition keeps the state");
+
+        assert!(pot.try_update(2).is_ok());
+        assert!(pot.try_update(1).is_ok());
+        assert_eq!(pot, Pot::Brewing(3));
+        assert_eq!(pot.try_update(1).err(), Some(PotError::AlreadyFilled));
+    }
+
+    #[test]
+    fn transitions_only_match_the_expected_state() {
+        let fill = |pot: &Pot| transition(pot, &Pot::Empty, || Pot::Filled(1), || Pot::Empty);
+        assert_eq!(fill(&Pot::Empty), Pot::Filled(1));
+        assert_eq!(fill(&Pot::Brewing(2)), Pot::Empty);
+    }
+}
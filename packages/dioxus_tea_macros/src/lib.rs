@@ -0,0 +1,465 @@
+//! Derive macros for [`dioxus-tea`](https://crates.io/crates/dioxus-tea).
+//!
+//! Use them through the `derive` feature of `dioxus-tea`, which re-exports them.
+
+#![warn(clippy::pedantic)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DataStruct, DeriveInput, Error, Fields, Path, Type};
+
+/// Implements `TeaModel` for a state, dispatching every variant of the action enum to a handler method.
+///
+/// A derive only sees the item it is attached to, so it goes on the action enum, and the state is named with the
+/// `#[tea(state = ...)]` attribute. Every variant is handled by a method of the state named `handle_` followed by
+/// the variant in snake case, which gets the fields of the variant as arguments, in order. Handlers return either
+/// `()` or a `Cmd`:
+///
+/// ```rust,ignore
+/// #[derive(TeaReducer)]
+/// #[tea(state = AppState)]
+/// pub enum AppStatusUpdate {
+///     CupFetched,
+///     AddWater(u8),
+/// }
+///
+/// impl AppState {
+///     fn handle_cup_fetched(&mut self) {
+///         self.status = Status::EmptyCup;
+///     }
+///
+///     fn handle_add_water(&mut self, temperature: u8) -> Cmd<AppStatusUpdate> {
+///         // ...
+///     }
+/// }
+/// ```
+#[proc_macro_derive(TeaReducer, attributes(tea))]
+pub fn derive_tea_reducer(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_tea_reducer(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_tea_reducer(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(actions) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "`TeaReducer` is derived for the action enum of a model",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`TeaReducer` does not support generic action enums",
+        ));
+    }
+
+    let state = state_type(input)?;
+    let action = &input.ident;
+    let arms = actions.variants.iter().map(|variant| {
+        let name = &variant.ident;
+        let handler = format_ident!("handle_{}", snake_case(&name.to_string()));
+        let (pattern, arguments) = match &variant.fields {
+            Fields::Unit => (quote!(#action::#name), Vec::new()),
+            Fields::Unnamed(fields) => {
+                let bindings: Vec<_> = (0..fields.unnamed.len())
+                    .map(|index| format_ident!("field_{index}"))
+                    .collect();
+                (quote!(#action::#name(#(#bindings),*)), bindings)
+            }
+            Fields::Named(fields) => {
+                let bindings: Vec<_> = fields.named.iter().filter_map(|field| field.ident.clone()).collect();
+                (quote!(#action::#name { #(#bindings),* }), bindings)
+            }
+        };
+        quote! {
+            #pattern => ::dioxus_tea::IntoCmd::into_cmd(self.#handler(#(#arguments),*))
+        }
+    });
+
+    Ok(quote! {
+        impl ::dioxus_tea::TeaModel for #state {
+            type Action = #action;
+
+            fn update(&mut self, action: Self::Action) -> ::dioxus_tea::Cmd<Self::Action> {
+                match action {
+                    #(#arms,)*
+                }
+            }
+        }
+    })
+}
+
+/// Generates a method for every variant of an action enum, so that `model.add_water(100)` sends
+/// `AppStatusUpdate::AddWater(100)`.
+///
+/// The methods are declared on an extension trait named after the enum with an `Actions` suffix, which is
+/// implemented for the `TeaModelSignal` of every model with these actions, and has to be in scope to call them.
+/// Every method is named after its variant in snake case, takes the fields of the variant as arguments, in order,
+/// and calls `send`:
+///
+/// ```rust,ignore
+/// #[derive(TeaActions)]
+/// pub enum AppStatusUpdate {
+///     CupFetched,
+///     AddWater(u8),
+/// }
+///
+/// // generates:
+/// pub trait AppStatusUpdateActions {
+///     fn cup_fetched(&self);
+///     fn add_water(&self, field_0: u8);
+/// }
+///
+/// impl<T: TeaModel<Action = AppStatusUpdate>> AppStatusUpdateActions for TeaModelSignal<T> {
+///     // ...
+/// }
+/// ```
+///
+/// The inherent methods of `TeaModelSignal` take precedence, so a variant named e.g. `Reset` can only be sent with
+/// `send` as before.
+#[proc_macro_derive(TeaActions)]
+pub fn derive_tea_actions(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_tea_actions(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_tea_actions(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(actions) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "`TeaActions` is derived for the action enum of a model",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`TeaActions` does not support generic action enums",
+        ));
+    }
+
+    let vis = &input.vis;
+    let action = &input.ident;
+    let actions_trait = format_ident!("{action}Actions");
+    let doc = format!("Sends the variants of [`{action}`] to a model, see `TeaActions`.");
+    let (declarations, methods): (Vec<_>, Vec<_>) = actions
+        .variants
+        .iter()
+        .map(|variant| {
+            let name = &variant.ident;
+            let method = format_ident!("{}", snake_case(&name.to_string()));
+            let doc = format!("Sends [`{action}::{name}`].");
+            let (parameters, constructor) = match &variant.fields {
+                Fields::Unit => (Vec::new(), quote!(#action::#name)),
+                Fields::Unnamed(fields) => {
+                    let bindings: Vec<_> = (0..fields.unnamed.len())
+                        .map(|index| format_ident!("field_{index}"))
+                        .collect();
+                    let parameters = bindings
+                        .iter()
+                        .zip(&fields.unnamed)
+                        .map(|(binding, field)| {
+                            let ty = &field.ty;
+                            quote!(#binding: #ty)
+                        })
+                        .collect();
+                    (parameters, quote!(#action::#name(#(#bindings),*)))
+                }
+                Fields::Named(fields) => {
+                    let bindings: Vec<_> = fields.named.iter().filter_map(|field| field.ident.as_ref()).collect();
+                    let parameters = fields
+                        .named
+                        .iter()
+                        .map(|field| {
+                            let (name, ty) = (&field.ident, &field.ty);
+                            quote!(#name: #ty)
+                        })
+                        .collect();
+                    (parameters, quote!(#action::#name { #(#bindings),* }))
+                }
+            };
+            let signature = quote!(fn #method(&self, #(#parameters),*));
+            (
+                quote! {
+                    #[doc = #doc]
+                    #signature;
+                },
+                quote! {
+                    #[inline]
+                    #signature {
+                        self.send(#constructor);
+                    }
+                },
+            )
+        })
+        .unzip();
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis trait #actions_trait {
+            #(#declarations)*
+        }
+
+        impl<T: ::dioxus_tea::TeaModel<Action = #action>> #actions_trait for ::dioxus_tea::TeaModelSignal<T> {
+            #(#methods)*
+        }
+    })
+}
+
+/// Generates a method for every field of a state, returning a lens that only follows that field, so that
+/// `model.status_signal()` re-renders its readers only when the status changed.
+///
+/// The methods are declared on an extension trait named after the state with a `Lenses` suffix, which is
+/// implemented for the `TeaModelSignal` of the state, and has to be in scope to call them. Every method is named
+/// after its field with a `_signal` suffix, and returns a `TeaLens`, which memoizes a clone of the field, so every
+/// field must be `Clone` and `PartialEq`:
+///
+/// ```rust,ignore
+/// #[derive(Clone, PartialEq, TeaLenses)]
+/// pub struct AppState {
+///     pub status: Status,
+///     pub kettle: KettleState,
+/// }
+///
+/// // generates:
+/// pub trait AppStateLenses {
+///     fn status_signal(&self) -> TeaLens<Status>;
+///     fn kettle_signal(&self) -> TeaLens<KettleState>;
+/// }
+///
+/// impl AppStateLenses for TeaModelSignal<AppState> {
+///     // ...
+/// }
+/// ```
+///
+/// Like `TeaModelSignal::map`, which the lenses are built on, every call creates a new memo owned by the current
+/// component, so create a lens once, e.g. in `use_hook`. A lens compares the whole field on every change of the
+/// state, and clones it whenever it changed: for a field that is a large struct itself, whose readers only need a
+/// part of it, project that part with `map` instead.
+#[proc_macro_derive(TeaLenses)]
+pub fn derive_tea_lenses(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_tea_lenses(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_tea_lenses(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = &input.data
+    else {
+        return Err(Error::new_spanned(
+            input,
+            "`TeaLenses` is derived for a state struct with named fields",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`TeaLenses` does not support generic states",
+        ));
+    }
+
+    let vis = &input.vis;
+    let state = &input.ident;
+    let lenses_trait = format_ident!("{state}Lenses");
+    let doc = format!("Lenses on the fields of [`{state}`], see `TeaLenses`.");
+    let (declarations, methods): (Vec<_>, Vec<_>) = fields
+        .named
+        .iter()
+        .filter_map(|field| field.ident.as_ref().map(|name| (name, &field.ty)))
+        .map(|(name, ty)| {
+            let method = format_ident!("{name}_signal");
+            let doc = format!("Returns a lens on [`{state}::{name}`], which only changes when the field does.");
+            let signature = quote!(fn #method(&self) -> ::dioxus_tea::TeaLens<#ty>);
+            (
+                quote! {
+                    #[doc = #doc]
+                    #signature;
+                },
+                quote! {
+                    #[inline]
+                    #signature {
+                        self.map(|state| ::core::clone::Clone::clone(&state.#name))
+                    }
+                },
+            )
+        })
+        .unzip();
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis trait #lenses_trait {
+            #(#declarations)*
+        }
+
+        impl #lenses_trait for ::dioxus_tea::TeaModelSignal<#state> {
+            #(#methods)*
+        }
+    })
+}
+
+/// Implements `TeaDiff` for a state, so that only what changed needs to be sent to devtools or other clients.
+///
+/// For a struct with named fields, this generates a delta struct named after the state with a `Delta` suffix,
+/// which holds an `Option` of every field: `Some` with the new value for the fields that changed, `None` for the
+/// others. Every field must be `Clone` and `PartialEq`. The delta derives `Clone` and `PartialEq`, and whatever
+/// else is listed in a `#[tea_diff(derive(...))]` attribute, e.g. the traits of serde to send it over the wire:
+///
+/// ```rust,ignore
+/// #[derive(Clone, PartialEq, TeaDiff)]
+/// #[tea_diff(derive(Debug, serde::Serialize, serde::Deserialize))]
+/// pub struct AppState {
+///     pub status: Status,
+///     pub kettle: KettleState,
+/// }
+///
+/// // generates:
+/// #[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+/// pub struct AppStateDelta {
+///     pub status: Option<Status>,
+///     pub kettle: Option<KettleState>,
+/// }
+/// ```
+///
+/// Enums and structs without named fields have no fields to diff one by one, so their delta is the new value
+/// itself: the derive sets `type Delta = Self`, and the type must be `Clone`.
+#[proc_macro_derive(TeaDiff, attributes(tea_diff))]
+pub fn derive_tea_diff(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_tea_diff(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand_tea_diff(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`TeaDiff` does not support generic states",
+        ));
+    }
+
+    let state = &input.ident;
+    let Data::Struct(DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = &input.data
+    else {
+        return Ok(quote! {
+            impl ::dioxus_tea::TeaDiff for #state {
+                type Delta = Self;
+
+                fn diff(_old: &Self, new: &Self) -> Self::Delta {
+                    ::core::clone::Clone::clone(new)
+                }
+
+                fn apply_delta(&mut self, delta: Self::Delta) {
+                    *self = delta;
+                }
+            }
+        });
+    };
+
+    let vis = &input.vis;
+    let delta = format_ident!("{state}Delta");
+    let doc = format!("The fields of [`{state}`] that changed, see `TeaDiff`.");
+    let derives = delta_derives(input)?;
+    let names: Vec<_> = fields.named.iter().filter_map(|field| field.ident.as_ref()).collect();
+    let declarations = fields.named.iter().map(|field| {
+        let (vis, name, ty) = (&field.vis, &field.ident, &field.ty);
+        quote!(#vis #name: ::core::option::Option<#ty>)
+    });
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(::core::clone::Clone, ::core::cmp::PartialEq, #(#derives),*)]
+        #vis struct #delta {
+            #(#declarations,)*
+        }
+
+        impl ::dioxus_tea::TeaDiff for #state {
+            type Delta = #delta;
+
+            fn diff(old: &Self, new: &Self) -> Self::Delta {
+                #delta {
+                    #(#names: (old.#names != new.#names).then(|| ::core::clone::Clone::clone(&new.#names)),)*
+                }
+            }
+
+            fn apply_delta(&mut self, delta: Self::Delta) {
+                #(
+                    if let ::core::option::Option::Some(value) = delta.#names {
+                        self.#names = value;
+                    }
+                )*
+            }
+        }
+    })
+}
+
+/// Returns the extra derives of a delta, listed in the `#[tea_diff(derive(...))]` attribute.
+fn delta_derives(input: &DeriveInput) -> syn::Result<Vec<Path>> {
+    let mut derives = Vec::new();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("tea_diff")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("derive") {
+                meta.parse_nested_meta(|derive| {
+                    derives.push(derive.path);
+                    Ok(())
+                })
+            } else {
+                Err(meta.error("expected `derive(...)`"))
+            }
+        })?;
+    }
+    Ok(derives)
+}
+
+/// Returns the state named by the `#[tea(state = ...)]` attribute.
+fn state_type(input: &DeriveInput) -> syn::Result<Type> {
+    let mut state = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("tea")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("state") {
+                state = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `state = ...`"))
+            }
+        })?;
+    }
+    state.ok_or_else(|| Error::new_spanned(&input.ident, "missing `#[tea(state = ...)]` attribute"))
+}
+
+/// Converts a `CamelCase` variant name into `snake_case`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variant_names_become_snake_case_handlers() {
+        assert_eq!(snake_case("CupFetched"), "cup_fetched");
+        assert_eq!(snake_case("AddWater"), "add_water");
+        assert_eq!(snake_case("Done"), "done");
+    }
+}